@@ -13,14 +13,53 @@ pub const MIN_SYMBOL_LENGTH: usize = 2;
 pub const MIN_MAX_SUPPLY: u64 = 1;
 pub const MAX_MAX_SUPPLY: u64 = 10000;
 
+// vault inventory
+pub const VAULT_INVENTORY_SEED: &[u8] = b"vault_inventory";
+pub const VAULT_INVENTORY_BYTES: usize = MAX_MAX_SUPPLY as usize / 8;
+
 // fee
 pub const MAX_ROYALTY_BASIS_POINTS: u16 = 10000; // 100%
-pub const REDEEM_FEE_BPS: u16 = 500; // 5%
+pub const MAX_REDEEM_FEE_BPS: u16 = 1000; // 10%
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 10000; // share of a collection fee
 pub const MAX_CREATOR_SHARE: u8 = 100;
 
+// pause flags
+pub const PAUSE_MINT: u8 = 1 << 0;
+pub const PAUSE_PULL: u8 = 1 << 1;
+pub const PAUSE_REDEEM: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_MINT | PAUSE_PULL | PAUSE_REDEEM;
+
+// batch mint, bounded by compute and transaction size
+pub const MAX_MINT_BATCH_SIZE: u8 = 5;
+pub const MINT_BATCH_ACCOUNTS: usize = 4;
+
+// batch redeem, more than a handful of groups needs an address lookup table.
+// A transaction locks at most MAX_TX_ACCOUNTS accounts; redeem_batch uses its
+// 11 named accounts and the program id, plus one group per NFT.
+pub const MAX_TX_ACCOUNTS: usize = 64;
+pub const REDEEM_BATCH_FIXED_ACCOUNTS: usize = 12;
+pub const REDEEM_BATCH_ACCOUNTS: usize = 4;
+pub const MAX_REDEEM_BATCH_SIZE: u8 =
+    ((MAX_TX_ACCOUNTS - REDEEM_BATCH_FIXED_ACCOUNTS) / REDEEM_BATCH_ACCOUNTS) as u8;
+
+// allowlist
+pub const MINT_PHASE_SEED: &[u8] = b"mint_phase";
+pub const MINT_RECORD_SEED: &[u8] = b"mint_record";
+
+// mint phases
+pub const MAX_MINT_PHASES: usize = 8;
+pub const PHASE_GATE_OPEN: u8 = 0;
+pub const PHASE_GATE_ALLOWLIST: u8 = 1;
+pub const PHASE_GATE_TOKEN: u8 = 2;
+pub const PHASE_GATE_TOKEN_ONCE: u8 = 3;
+pub const GATE_RECORD_SEED: &[u8] = b"gate_record";
+
+// random pull
+pub const PULL_COMMITMENT_SEED: &[u8] = b"pull_commitment";
+
 // queue
 pub const MAX_QUEUE_SIZE: usize = 10_000;
 
 // token
-pub const FEE_RECEIVER: &str = "DfD5WCDk11NwW1uirpvCszKSvfnAJUR3xHoFJ9E1noAn";
+pub const MAX_ALLOWED_PAYMENT_MINTS: usize = 16;
 
@@ -81,4 +81,142 @@ pub enum InterchangeableNFTError {
 
     #[msg("Invalid NFT creator")]
     InvalidNFTCreator,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+
+    #[msg("Vault balance cannot cover redeem backing")]
+    InsufficientBacking,
+
+    #[msg("Signer is not the pending authority")]
+    InvalidPendingAuthority,
+
+    #[msg("Redeem fee exceeds protocol maximum")]
+    RedeemFeeTooHigh,
+
+    #[msg("Only program upgrade authority can perform this action")]
+    InvalidUpgradeAuthority,
+
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+
+    #[msg("Payment token is not allowed by the protocol")]
+    PaymentMintNotAllowed,
+
+    #[msg("Protocol is in redeem-only mode")]
+    ProtocolRedeemOnly,
+
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("Vault inventory does not match vault contents")]
+    VaultInventoryMismatch,
+
+    #[msg("Invalid token id")]
+    InvalidTokenId,
+
+    #[msg("Token id already registered")]
+    TokenAlreadyRegistered,
+
+    #[msg("Invalid token registry")]
+    InvalidTokenRegistry,
+
+    #[msg("Invalid SlotHashes sysvar")]
+    InvalidSlotHashes,
+
+    #[msg("Seed does not match commitment")]
+    InvalidCommitmentSeed,
+
+    #[msg("Commitment cannot be revealed yet")]
+    CommitmentNotReady,
+
+    #[msg("Commitment expired")]
+    CommitmentExpired,
+
+    #[msg("Commitment has not expired")]
+    CommitmentNotExpired,
+
+    #[msg("Invalid batch size")]
+    InvalidBatchSize,
+
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+
+    #[msg("Payout is below the minimum amount out")]
+    SlippageExceeded,
+
+    #[msg("Price exceeds the maximum payment")]
+    PriceExceedsMaxPayment,
+
+    #[msg("Public mint is closed during the allowlist phase")]
+    PublicMintClosed,
+
+    #[msg("Invalid allowlist proof")]
+    InvalidAllowlistProof,
+
+    #[msg("Allowlist quota exceeded")]
+    AllowlistQuotaExceeded,
+
+    #[msg("Allowlist price is below the mint price")]
+    InvalidAllowlistPrice,
+
+    #[msg("Invalid mint phases")]
+    InvalidMintPhases,
+
+    #[msg("No mint phase is active")]
+    MintPhaseClosed,
+
+    #[msg("Active mint phase requires a different mint instruction")]
+    MintPhaseGated,
+
+    #[msg("Phase price is below the mint price")]
+    InvalidPhasePrice,
+
+    #[msg("Wallet mint limit exceeded")]
+    WalletMintLimitExceeded,
+
+    #[msg("Gate NFT is not held by the minter")]
+    InvalidGateNFT,
+
+    #[msg("Gate NFT was already used")]
+    GateNFTAlreadyUsed,
+
+    #[msg("Gate record account is required")]
+    MissingGateRecord,
+
+    #[msg("Reserved supply exceeded")]
+    ReservedSupplyExceeded,
+
+    #[msg("Invalid reserved supply")]
+    InvalidReservedSupply,
+
+    #[msg("Collection is not awaiting reveal")]
+    NotPreReveal,
+
+    #[msg("Collection is already revealed")]
+    CollectionAlreadyRevealed,
+
+    #[msg("Invalid id pool")]
+    InvalidIdPool,
+
+    #[msg("Vault inventory changed since the commitment")]
+    VaultInventoryChanged,
+
+    #[msg("Swap fee exceeds protocol maximum")]
+    SwapFeeTooHigh,
+
+    #[msg("Reveal already pending")]
+    RevealPending,
+
+    #[msg("Reveal has not been requested")]
+    RevealNotRequested,
+
+    #[msg("Mint price cannot be lowered while NFTs are in circulation")]
+    PriceCutWithCirculatingSupply,
+
+    #[msg("Provenance and offset seed hashes must be set")]
+    InvalidRevealCommitment,
+
+    #[msg("Mint phases are scheduled, use set_mint_phases")]
+    MintPhasesScheduled,
 }
\ No newline at end of file
@@ -11,24 +11,77 @@ pub struct CollectionInitialized {
     pub collection_name: String,
     pub collection_symbol: String,
     pub collection_uri: String,
+    pub provenance_hash: [u8; 32],
     pub timestamp: i64,
 }
 
 #[event]
 pub struct NFTMinted {
     pub minter: Pubkey,
+    pub recipient: Pubkey,
     pub collection_mint: Pubkey,
     pub token_id: u64,
+    // None until the collection is revealed
+    pub metadata_id: Option<u64>,
     pub mint: Pubkey,
     pub amount: u64,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct NFTBatchMinted {
+    pub minter: Pubkey,
+    pub recipient: Pubkey,
+    pub collection_mint: Pubkey,
+    pub first_token_id: u64,
+    pub last_token_id: u64,
+    pub mints: Vec<Pubkey>,
+    // in mint order, empty until the collection is revealed
+    pub metadata_ids: Vec<u64>,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintPhaseUpdated {
+    pub collection_mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub allowlist_only: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintPhasesUpdated {
+    pub collection_mint: Pubkey,
+    pub phase_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollectionRevealed {
+    pub collection_mint: Pubkey,
+    pub base_uri: String,
+    pub provenance_hash: [u8; 32],
+    pub reveal_offset: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct NFTRedeemed {
     pub redeemer: Pubkey,
     pub collection_mint: Pubkey,
     pub nft_mint: Pubkey,
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+    pub fee_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NFTBatchRedeemed {
+    pub redeemer: Pubkey,
+    pub collection_mint: Pubkey,
+    pub nft_mints: Vec<Pubkey>,
     pub amount: u64,
     pub fee_amount: u64,
     pub timestamp: i64,
@@ -53,6 +106,159 @@ pub struct NFTPull {
     pub puller: Pubkey,
     pub collection_mint: Pubkey,
     pub mint: Pubkey,
+    pub token_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollectionConfigUpdated {
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub old_mint_price: u64,
+    pub new_mint_price: u64,
+    pub old_max_supply: u64,
+    pub new_max_supply: u64,
+    pub old_base_uri: String,
+    pub new_base_uri: String,
+    pub old_max_mints_per_wallet: Option<u32>,
+    pub new_max_mints_per_wallet: Option<u32>,
+    pub old_reserved_supply: u64,
+    pub new_reserved_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeConfigUpdated {
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub old_redeem_fee: u16,
+    pub new_redeem_fee: u16,
+    pub old_fee_receiver: Pubkey,
+    pub new_fee_receiver: Pubkey,
+    pub old_swap_fee: u16,
+    pub new_swap_fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProgramStateUpdated {
+    pub admin: Pubkey,
+    pub fee_receiver: Pubkey,
+    pub default_redeem_fee: u16,
+    pub max_redeem_fee: u16,
+    pub paused: bool,
+    pub allowed_payment_mints: Vec<Pubkey>,
+    pub protocol_fee_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolPauseChanged {
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub redeem_only: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollectionPauseFlagsChanged {
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub old_pause_flags: u8,
+    pub new_pause_flags: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub required_backing: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReservesVerified {
+    pub collection_mint: Pubkey,
+    pub vault_balance: u64,
+    pub required_backing: u64,
+    pub circulating_supply: u64,
+    pub vault_nft_count: u64,
+    pub backing_ratio_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultInventorySynced {
+    pub collection_mint: Pubkey,
+    pub nft_mint: Pubkey,
+    pub token_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NFTRegistered {
+    pub collection_mint: Pubkey,
+    pub nft_mint: Pubkey,
+    pub token_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RandomPullCommitted {
+    pub puller: Pubkey,
+    pub collection_mint: Pubkey,
+    pub commit_slot: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NFTRandomPull {
+    pub puller: Pubkey,
+    pub collection_mint: Pubkey,
+    pub mint: Pubkey,
+    pub token_id: u64,
     pub amount: u64,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct RandomPullCancelled {
+    pub puller: Pubkey,
+    pub collection_mint: Pubkey,
+    pub refund_amount: u64,
+    pub fee_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NFTSwapped {
+    pub swapper: Pubkey,
+    pub collection_mint: Pubkey,
+    pub in_mint: Pubkey,
+    pub in_token_id: u64,
+    pub out_mint: Pubkey,
+    pub out_token_id: u64,
+    pub fee_amount: u64,
+    pub timestamp: i64,
+}
@@ -17,6 +17,7 @@ pub mod interchangeable_nft {
 
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         ctx: Context<Initialize>,
         mint_price: u64,
@@ -27,6 +28,8 @@ pub mod interchangeable_nft {
         collection_uri: String,
         royalty_fee_basis_points: u16,
         royalty_fee_receiver: Pubkey,
+        redeem_fee_bps: Option<u16>,
+        pre_reveal: Option<PreRevealConfig>,
     ) -> Result<()> {
         processor::initialize::process_initialize(
             ctx,
@@ -38,27 +41,211 @@ pub mod interchangeable_nft {
             collection_uri,
             royalty_fee_basis_points,
             royalty_fee_receiver,
+            redeem_fee_bps,
+            pre_reveal,
         )
     }
 
-    pub fn mint_nft(ctx: Context<MintNFT>) -> Result<()> {
-        processor::mint::process_mint(ctx)
+    pub fn mint_nft(ctx: Context<MintNFT>, max_payment: u64) -> Result<()> {
+        processor::mint::process_mint(ctx, max_payment)
     }
 
-    pub fn pull_nft(ctx: Context<PullNft>) -> Result<()> {
-        processor::pull::pull_nft(ctx)
+    pub fn mint_allowlisted(
+        ctx: Context<MintAllowlisted>,
+        proof: Vec<[u8; 32]>,
+        quota: u32,
+        price: u64,
+        max_payment: u64,
+    ) -> Result<()> {
+        processor::phase::process_mint_allowlisted(ctx, proof, quota, price, max_payment)
+    }
+
+    pub fn mint_token_gated(ctx: Context<MintTokenGated>, max_payment: u64) -> Result<()> {
+        processor::phase::process_mint_token_gated(ctx, max_payment)
+    }
+
+    pub fn mint_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MintBatch<'info>>,
+        count: u8,
+        max_payment: u64,
+    ) -> Result<()> {
+        processor::mint::process_mint_batch(ctx, count, max_payment)
+    }
+
+    pub fn authority_mint<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AuthorityMint<'info>>,
+        count: u8,
+    ) -> Result<()> {
+        processor::mint::process_authority_mint(ctx, count)
+    }
+
+    pub fn pull_nft(ctx: Context<PullNft>, max_payment: u64) -> Result<()> {
+        processor::pull::pull_nft(ctx, max_payment)
+    }
+
+    pub fn pull_nft_by_id(ctx: Context<PullNftById>, token_id: u64, max_payment: u64) -> Result<()> {
+        processor::pull::pull_nft_by_id(ctx, token_id, max_payment)
+    }
+
+    pub fn commit_pull_random(
+        ctx: Context<CommitPullRandom>,
+        seed_hash: [u8; 32],
+        max_payment: u64,
+    ) -> Result<()> {
+        processor::random::process_commit_pull_random(ctx, seed_hash, max_payment)
+    }
+
+    pub fn reveal_pull_random(ctx: Context<RevealPullRandom>, seed: [u8; 32]) -> Result<()> {
+        processor::random::process_reveal_pull_random(ctx, seed)
+    }
+
+    pub fn cancel_pull_random(ctx: Context<CancelPullRandom>) -> Result<()> {
+        processor::random::process_cancel_pull_random(ctx)
     }
 
     pub fn redeem_nft(ctx: Context<RedeemNFT>) -> Result<()> {
         processor::redeem::process_redeem(ctx)
     }
 
-    pub fn pause(ctx: Context<CollectionAuthority>) -> Result<()> {
+    pub fn swap_nft(ctx: Context<SwapNft>) -> Result<()> {
+        processor::swap::process_swap(ctx)
+    }
+
+    pub fn redeem_to(ctx: Context<RedeemTo>, min_amount_out: u64) -> Result<()> {
+        processor::redeem::process_redeem_to(ctx, min_amount_out)
+    }
+
+    pub fn redeem_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemBatch<'info>>,
+        count: u8,
+    ) -> Result<()> {
+        processor::redeem::process_redeem_batch(ctx, count)
+    }
+
+    pub fn set_mint_phase(
+        ctx: Context<SetMintPhase>,
+        merkle_root: [u8; 32],
+        allowlist_only: bool,
+    ) -> Result<()> {
+        processor::phase::process_set_mint_phase(ctx, merkle_root, allowlist_only)
+    }
+
+    pub fn set_mint_phases(ctx: Context<SetMintPhases>, phases: Vec<MintPhase>) -> Result<()> {
+        processor::phase::process_set_mint_phases(ctx, phases)
+    }
+
+    pub fn reveal(ctx: Context<Reveal>, base_uri: String, offset_seed: [u8; 32]) -> Result<()> {
+        processor::reveal::process_reveal(ctx, base_uri, offset_seed)
+    }
+
+    pub fn settle_reveal(ctx: Context<SettleReveal>) -> Result<()> {
+        processor::reveal::process_settle_reveal(ctx)
+    }
+
+    pub fn update_revealed_metadata<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateRevealedMetadata<'info>>,
+    ) -> Result<()> {
+        processor::reveal::process_update_revealed_metadata(ctx)
+    }
+
+    pub fn pause(ctx: Context<AdminOnly>) -> Result<()> {
         processor::admin::process_pause(ctx)
     }
 
-    pub fn unpause(ctx: Context<CollectionAuthority>) -> Result<()> {
+    pub fn unpause(ctx: Context<AdminOnly>) -> Result<()> {
         processor::admin::process_unpause(ctx)
     }
 
+    pub fn set_pause_flags(ctx: Context<AdminOnly>, pause_flags: u8) -> Result<()> {
+        processor::admin::process_set_pause_flags(ctx, pause_flags)
+    }
+
+    pub fn update_collection_config(
+        ctx: Context<UpdateCollectionConfig>,
+        mint_price: Option<u64>,
+        max_supply: Option<u64>,
+        base_uri: Option<String>,
+        max_mints_per_wallet: Option<u32>,
+        reserved_supply: Option<u64>,
+    ) -> Result<()> {
+        processor::admin::process_update_collection_config(
+            ctx,
+            mint_price,
+            max_supply,
+            base_uri,
+            max_mints_per_wallet,
+            reserved_supply,
+        )
+    }
+
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfig>,
+        redeem_fee_bps: u16,
+        swap_fee_bps: u16,
+    ) -> Result<()> {
+        processor::admin::process_update_fee_config(ctx, redeem_fee_bps, swap_fee_bps)
+    }
+
+    pub fn withdraw_surplus(ctx: Context<WithdrawSurplus>, amount: u64) -> Result<()> {
+        processor::treasury::process_withdraw_surplus(ctx, amount)
+    }
+
+    pub fn verify_reserves(ctx: Context<VerifyReserves>) -> Result<ReservesReport> {
+        processor::treasury::process_verify_reserves(ctx)
+    }
+
+    pub fn initialize_vault_inventory(ctx: Context<InitializeVaultInventory>) -> Result<()> {
+        processor::inventory::process_initialize_vault_inventory(ctx)
+    }
+
+    pub fn sync_vault_inventory(ctx: Context<SyncVaultInventory>) -> Result<()> {
+        processor::inventory::process_sync_vault_inventory(ctx)
+    }
+
+    pub fn initialize_token_registry(ctx: Context<InitializeTokenRegistry>) -> Result<()> {
+        processor::registry::process_initialize_token_registry(ctx)
+    }
+
+    pub fn initialize_id_pool(ctx: Context<InitializeIdPool>) -> Result<()> {
+        processor::registry::process_initialize_id_pool(ctx)
+    }
+
+    pub fn register_nft(ctx: Context<RegisterNft>) -> Result<()> {
+        processor::registry::process_register_nft(ctx)
+    }
+
+    pub fn propose_authority(ctx: Context<AdminOnly>, new_authority: Pubkey) -> Result<()> {
+        processor::admin::process_propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        processor::admin::process_accept_authority(ctx)
+    }
+
+    pub fn migrate_collection_state(ctx: Context<MigrateCollectionState>) -> Result<()> {
+        processor::admin::process_migrate_collection_state(ctx)
+    }
+
+    pub fn initialize_program_state(
+        ctx: Context<InitializeProgramState>,
+        params: ProgramStateParams,
+    ) -> Result<()> {
+        processor::protocol::process_initialize_program_state(ctx, params)
+    }
+
+    pub fn update_program_state(
+        ctx: Context<ProtocolAdmin>,
+        params: ProgramStateParams,
+    ) -> Result<()> {
+        processor::protocol::process_update_program_state(ctx, params)
+    }
+
+    pub fn set_protocol_pause(
+        ctx: Context<ProtocolAdmin>,
+        paused: bool,
+        redeem_only: bool,
+    ) -> Result<()> {
+        processor::protocol::process_set_protocol_pause(ctx, paused, redeem_only)
+    }
+
 }      
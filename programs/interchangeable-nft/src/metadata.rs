@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
+use mpl_token_metadata::accounts::Metadata;
 use mpl_token_metadata::types::{Creator, DataV2, Collection};
 
+use crate::{error::InterchangeableNFTError, state::CollectionState};
+
 // create nft metadata helper function
 pub fn create_nft_metadata_data(
     name: String,
@@ -58,3 +61,73 @@ pub fn create_collection_metadata_data(
         uses: None,
     }
 }
+
+// verify an NFT is a verified member of `collection_mint`
+pub fn verify_collection_membership(
+    nft_metadata: &AccountInfo,
+    collection_mint: &Pubkey,
+) -> Result<Metadata> {
+    // 0.1 verify NFT Metadata
+    let nft_metadata = Metadata::try_from(nft_metadata)?;
+    require!(nft_metadata.collection.is_some(), InterchangeableNFTError::InvalidCollectionNFT);
+    
+    let collection = nft_metadata.collection.as_ref().unwrap();
+    
+    // 0.2 verify Collection
+    require!(
+        collection.key == *collection_mint,
+        InterchangeableNFTError::InvalidCollectionNFT
+    );
+    require!(
+        collection.verified,
+        InterchangeableNFTError::UnverifiedCollection
+    );
+
+    Ok(nft_metadata)
+}
+
+// creator and update authority of every NFT in the collection
+pub fn pda_creator_address(collection_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            crate::ID.as_ref(),
+            collection_mint.as_ref(),
+            b"pda_creator",
+        ],
+        &crate::ID
+    ).0
+}
+
+// verify an NFT belongs to the collection and return its token_id and metadata;
+// `pda_creator` is pda_creator_address of the collection, derived once by
+// callers that check several NFTs
+pub fn verify_collection_nft(
+    nft_metadata: &AccountInfo,
+    collection_state: &CollectionState,
+    pda_creator: &Pubkey,
+) -> Result<(u64, Metadata)> {
+    let nft_metadata = verify_collection_membership(nft_metadata, &collection_state.collection_mint)?;
+
+    // 0.3 verify Creator
+    let creators = nft_metadata.creators.as_ref()
+        .ok_or(InterchangeableNFTError::InvalidNFTCreator)?;
+
+    require!(
+        creators.iter().any(|c| c.address == *pda_creator && c.verified),
+        InterchangeableNFTError::InvalidNFTCreator
+    );
+
+    let token_id = token_id_from_name(&nft_metadata.name)?;
+    Ok((token_id, nft_metadata))
+}
+
+// NFT names are written by mint_single_nft as "{collection name} #{token_id}"
+// and only pda_creator, checked above, can change them. Only the suffix is
+// read, so the id does not depend on collection_state.name. The metadata
+// program pads names with trailing zero bytes.
+pub fn token_id_from_name(name: &str) -> Result<u64> {
+    name.trim_end_matches(char::from(0))
+        .rsplit_once(" #")
+        .and_then(|(_, token_id)| token_id.parse::<u64>().ok())
+        .ok_or_else(|| error!(InterchangeableNFTError::InvalidTokenId))
+}
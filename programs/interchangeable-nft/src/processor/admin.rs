@@ -1,40 +1,305 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program, Discriminator};
 use crate::{
     state::*,
     events::*,
+    error::*,
+    constants::*,
 };
 
-pub fn process_pause(ctx: Context<CollectionAuthority>) -> Result<()> {
+pub fn process_pause(ctx: Context<AdminOnly>) -> Result<()> {
     let collection_state = &mut ctx.accounts.collection_state;
+    let old_pause_flags = collection_state.pause_flags;
     
-    // verify caller is collection authority
-    collection_state.validate_authority(&ctx.accounts.authority.key())?;
-    
-    collection_state.paused = true;
+    collection_state.pause_flags = PAUSE_ALL;
     
+    let timestamp = Clock::get()?.unix_timestamp;
     emit!(CollectionPaused {
         authority: ctx.accounts.authority.key(),
         collection_mint: collection_state.collection_mint,
-        timestamp: Clock::get()?.unix_timestamp,
+        timestamp,
+    });
+    emit!(CollectionPauseFlagsChanged {
+        authority: ctx.accounts.authority.key(),
+        collection_mint: collection_state.collection_mint,
+        old_pause_flags,
+        new_pause_flags: PAUSE_ALL,
+        timestamp,
     });
     
     Ok(())
 }
 
-pub fn process_unpause(ctx: Context<CollectionAuthority>) -> Result<()> {
+pub fn process_unpause(ctx: Context<AdminOnly>) -> Result<()> {
     let collection_state = &mut ctx.accounts.collection_state;
+    let old_pause_flags = collection_state.pause_flags;
     
-    // verify caller is collection authority
-    collection_state.validate_authority(&ctx.accounts.authority.key())?;
-    
-    collection_state.paused = false;
+    collection_state.pause_flags = 0;
     
+    let timestamp = Clock::get()?.unix_timestamp;
     emit!(CollectionUnpaused {
         authority: ctx.accounts.authority.key(),
         collection_mint: collection_state.collection_mint,
-        timestamp: Clock::get()?.unix_timestamp,
+        timestamp,
+    });
+    emit!(CollectionPauseFlagsChanged {
+        authority: ctx.accounts.authority.key(),
+        collection_mint: collection_state.collection_mint,
+        old_pause_flags,
+        new_pause_flags: 0,
+        timestamp,
     });
     
     Ok(())
 }
 
+
+
+pub fn process_set_pause_flags(ctx: Context<AdminOnly>, pause_flags: u8) -> Result<()> {
+    require!(
+        pause_flags & !PAUSE_ALL == 0,
+        InterchangeableNFTError::InvalidPauseFlags
+    );
+
+    let collection_state = &mut ctx.accounts.collection_state;
+    let old_pause_flags = collection_state.pause_flags;
+    collection_state.pause_flags = pause_flags;
+
+    emit!(CollectionPauseFlagsChanged {
+        authority: ctx.accounts.authority.key(),
+        collection_mint: collection_state.collection_mint,
+        old_pause_flags,
+        new_pause_flags: pause_flags,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_update_collection_config(
+    ctx: Context<UpdateCollectionConfig>,
+    mint_price: Option<u64>,
+    max_supply: Option<u64>,
+    base_uri: Option<String>,
+    max_mints_per_wallet: Option<u32>,
+    reserved_supply: Option<u64>,
+) -> Result<()> {
+    let collection_state = &mut ctx.accounts.admin.collection_state;
+
+    let old_mint_price = collection_state.mint_price;
+    let old_max_supply = collection_state.max_supply;
+    let old_base_uri = collection_state.base_uri.clone();
+    let old_max_mints_per_wallet = collection_state.max_mints_per_wallet;
+    let old_reserved_supply = collection_state.reserved_supply;
+
+    if let Some(mint_price) = mint_price {
+        require!(mint_price > 0, InterchangeableNFTError::InvalidMintPrice);
+
+        // circulating NFTs were bought at the old price; a cut would lower
+        // their redeem value and free the difference for withdraw_surplus
+        require!(
+            mint_price >= collection_state.mint_price || collection_state.circulating_supply() == 0,
+            InterchangeableNFTError::PriceCutWithCirculatingSupply
+        );
+        // the vault was funded at the old price, so it must already hold
+        // enough to redeem every circulating NFT at the new one
+        require!(
+            ctx.accounts.vault_token_account.amount >= collection_state.required_backing(mint_price)?,
+            InterchangeableNFTError::InsufficientBacking
+        );
+        collection_state.mint_price = mint_price;
+    }
+
+    if let Some(max_supply) = max_supply {
+        // the reveal offset is taken modulo max_supply
+        require!(!collection_state.revealed, InterchangeableNFTError::CollectionAlreadyRevealed);
+        // the id pool only covers the ids below max_supply
+        require!(
+            collection_state.id_pool == Pubkey::default(),
+            InterchangeableNFTError::InvalidMaxSupply
+        );
+        require!(
+            (MIN_MAX_SUPPLY..=MAX_MAX_SUPPLY).contains(&max_supply),
+            InterchangeableNFTError::InvalidMaxSupply
+        );
+        require!(
+            max_supply >= collection_state.next_token_id,
+            InterchangeableNFTError::InvalidMaxSupply
+        );
+        collection_state.max_supply = max_supply;
+    }
+
+    if let Some(base_uri) = base_uri {
+        require!(
+            base_uri.len() <= MAX_URI_LENGTH,
+            InterchangeableNFTError::InvalidBaseURI
+        );
+        collection_state.base_uri = base_uri;
+    }
+
+    // Some(0) removes the limit
+    if let Some(max_mints_per_wallet) = max_mints_per_wallet {
+        collection_state.max_mints_per_wallet = Some(max_mints_per_wallet).filter(|max| *max > 0);
+    }
+
+    if let Some(reserved_supply) = reserved_supply {
+        require!(
+            reserved_supply >= collection_state.reserved_minted,
+            InterchangeableNFTError::InvalidReservedSupply
+        );
+        collection_state.reserved_supply = reserved_supply;
+    }
+
+    // the unminted reserve must still fit in max_supply
+    require!(
+        collection_state.next_token_id
+            .checked_add(collection_state.reserved_remaining())
+            .ok_or(InterchangeableNFTError::MathOverflow)? <= collection_state.max_supply,
+        InterchangeableNFTError::InvalidReservedSupply
+    );
+
+    emit!(CollectionConfigUpdated {
+        authority: ctx.accounts.admin.authority.key(),
+        collection_mint: collection_state.collection_mint,
+        old_mint_price,
+        new_mint_price: collection_state.mint_price,
+        old_max_supply,
+        new_max_supply: collection_state.max_supply,
+        old_base_uri,
+        new_base_uri: collection_state.base_uri.clone(),
+        old_max_mints_per_wallet,
+        new_max_mints_per_wallet: collection_state.max_mints_per_wallet,
+        old_reserved_supply,
+        new_reserved_supply: collection_state.reserved_supply,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_update_fee_config(
+    ctx: Context<UpdateFeeConfig>,
+    redeem_fee_bps: u16,
+    swap_fee_bps: u16,
+) -> Result<()> {
+    ctx.accounts.program_state.validate_redeem_fee(redeem_fee_bps)?;
+    ctx.accounts.program_state.validate_swap_fee(swap_fee_bps)?;
+
+    let collection_state = &mut ctx.accounts.admin.collection_state;
+    let old_redeem_fee = collection_state.redeem_fee;
+    let old_fee_receiver = collection_state.fee_receiver;
+    let old_swap_fee = collection_state.swap_fee;
+
+    collection_state.redeem_fee = redeem_fee_bps;
+    collection_state.swap_fee = swap_fee_bps;
+    collection_state.fee_receiver = ctx.accounts.fee_receiver.key();
+
+    emit!(FeeConfigUpdated {
+        authority: ctx.accounts.admin.authority.key(),
+        collection_mint: collection_state.collection_mint,
+        old_redeem_fee,
+        new_redeem_fee: redeem_fee_bps,
+        old_fee_receiver,
+        new_fee_receiver: collection_state.fee_receiver,
+        old_swap_fee,
+        new_swap_fee: swap_fee_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_propose_authority(ctx: Context<AdminOnly>, new_authority: Pubkey) -> Result<()> {
+    let collection_state = &mut ctx.accounts.collection_state;
+
+    collection_state.pending_authority = Some(new_authority);
+
+    emit!(AuthorityProposed {
+        authority: ctx.accounts.authority.key(),
+        pending_authority: new_authority,
+        collection_mint: collection_state.collection_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let collection_state = &mut ctx.accounts.collection_state;
+    let old_authority = collection_state.authority;
+
+    collection_state.authority = ctx.accounts.pending_authority.key();
+    collection_state.pending_authority = None;
+
+    emit!(AuthorityTransferred {
+        old_authority,
+        new_authority: collection_state.authority,
+        collection_mint: collection_state.collection_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_migrate_collection_state(ctx: Context<MigrateCollectionState>) -> Result<()> {
+    let account = ctx.accounts.collection_state.to_account_info();
+
+    // grow the account first, the fields added since it was created do not
+    // fit in the old allocation
+    let rent_due = Rent::get()?
+        .minimum_balance(CollectionState::SPACE)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    if account.data_len() < CollectionState::SPACE {
+        account.realloc(CollectionState::SPACE, true)?;
+    }
+
+    let mut data = account.try_borrow_mut_data()?;
+    require!(
+        data[..8] == CollectionState::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let mut rest = &data[8..];
+    let legacy = LegacyCollectionState::deserialize(&mut rest)?;
+    require!(
+        legacy.authority == ctx.accounts.authority.key(),
+        InterchangeableNFTError::OnlyOwner
+    );
+
+    // legacy accounts end at token_decimals and the zeroed padding after it
+    // reads as an unset seed_authority
+    let seed_authority = Pubkey::deserialize(&mut rest)?;
+    let collection_state = if seed_authority == Pubkey::default() {
+        legacy.migrate()
+    } else {
+        CollectionState::try_deserialize(&mut &data[..])?
+    };
+
+    let expected = Pubkey::create_program_address(
+        &[
+            b"collection",
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref(),
+            &[collection_state.bump],
+        ],
+        &crate::ID,
+    ).map_err(|_| error!(InterchangeableNFTError::PubkeyMismatch))?;
+    require!(
+        expected == account.key(),
+        InterchangeableNFTError::PubkeyMismatch
+    );
+
+    collection_state.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -12,6 +12,7 @@ use crate::{
     constants::*, error::*, events::*, state::*, metadata::*,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub fn process_initialize(
     ctx: Context<Initialize>,
     mint_price: u64,
@@ -22,13 +23,15 @@ pub fn process_initialize(
     collection_uri: String,
     royalty_fee_basis_points: u16,
     royalty_fee_receiver: Pubkey,
+    redeem_fee_bps: Option<u16>,
+    pre_reveal: Option<PreRevealConfig>,
 ) -> Result<()> {
     msg!("=== Starting process_initialize ===");
     
     // Validate parameters
     msg!("Validating parameters...");
     require!(
-        max_supply >= MIN_MAX_SUPPLY && max_supply <= MAX_MAX_SUPPLY,
+        (MIN_MAX_SUPPLY..=MAX_MAX_SUPPLY).contains(&max_supply),
         InterchangeableNFTError::InvalidMaxSupply
     );
     
@@ -37,11 +40,30 @@ pub fn process_initialize(
         InterchangeableNFTError::InvalidBaseURI
     );
     
+    if let Some(pre_reveal) = &pre_reveal {
+        require!(
+            !pre_reveal.placeholder_uri.is_empty() && pre_reveal.placeholder_uri.len() <= MAX_URI_LENGTH,
+            InterchangeableNFTError::InvalidBaseURI
+        );
+        // an all-zero hash commits to nothing
+        require!(
+            pre_reveal.provenance_hash != [0; 32] && pre_reveal.offset_seed_hash != [0; 32],
+            InterchangeableNFTError::InvalidRevealCommitment
+        );
+    }
+    
     require!(
         royalty_fee_basis_points <= MAX_ROYALTY_BASIS_POINTS,
         InterchangeableNFTError::InvalidRoyalty
     );
 
+    let program_state = &ctx.accounts.program_state;
+    program_state.check_entry_allowed()?;
+    program_state.validate_payment_mint(&ctx.accounts.payment_token_mint.key())?;
+
+    let redeem_fee_bps = redeem_fee_bps.unwrap_or(program_state.default_redeem_fee);
+    program_state.validate_redeem_fee(redeem_fee_bps)?;
+
     // Calculate PDA seeds and bump
     msg!("Calculating PDA...");
     let collection_mint_key = ctx.accounts.collection_mint.key();
@@ -182,18 +204,39 @@ pub fn process_initialize(
     collection_state.collection_mint = ctx.accounts.collection_mint.key();
     collection_state.base_uri = base_uri.clone();
     collection_state.next_token_id = 0;
-    collection_state.paused = false;
+    collection_state.pause_flags = 0;
     collection_state.name = collection_name.clone();
     collection_state.symbol = collection_symbol.clone();
     collection_state.royalty_config.basis_points = royalty_fee_basis_points;
     collection_state.royalty_config.receiver = royalty_fee_receiver;
-    collection_state.fee_receiver = Pubkey::try_from(FEE_RECEIVER)
-        .map_err(|_| error!(InterchangeableNFTError::InvalidFeeReceiver))?;
-    collection_state.redeem_fee = REDEEM_FEE_BPS;
+    collection_state.fee_receiver = ctx.accounts.fee_receiver.key();
+    collection_state.redeem_fee = redeem_fee_bps;
     collection_state.bump = ctx.bumps.collection_state;
+    collection_state.seed_authority = ctx.accounts.authority.key();
+    collection_state.pending_authority = None;
+    collection_state.vault_nft_count = 0;
+    collection_state.swap_fee = 0;
+    collection_state.max_mints_per_wallet = None;
+    collection_state.reserved_supply = 0;
+    collection_state.reserved_minted = 0;
+    if let Some(pre_reveal) = pre_reveal {
+        collection_state.placeholder_uri = pre_reveal.placeholder_uri;
+        collection_state.provenance_hash = pre_reveal.provenance_hash;
+        collection_state.offset_seed_hash = pre_reveal.offset_seed_hash;
+    }
+    collection_state.revealed = false;
+    collection_state.reveal_offset = 0;
+    collection_state.id_pool = Pubkey::default();
+    collection_state.offset_seed = [0; 32];
+    collection_state.reveal_slot = 0;
 
     collection_state.token_decimals = ctx.accounts.payment_token_mint.decimals;
 
+    let mut vault_inventory = ctx.accounts.vault_inventory.load_init()?;
+    vault_inventory.collection_state = collection_state.key();
+    vault_inventory.bump = ctx.bumps.vault_inventory;
+    drop(vault_inventory);
+
     // 5. Emit Collection Initialized Event
     emit!(CollectionInitialized {
         authority: ctx.accounts.authority.key(),
@@ -205,6 +248,7 @@ pub fn process_initialize(
         collection_name: collection_name.clone(),
         collection_symbol: collection_symbol.clone(),
         collection_uri: collection_uri.clone(),
+        provenance_hash: collection_state.provenance_hash,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
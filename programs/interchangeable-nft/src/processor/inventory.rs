@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::{
+    error::InterchangeableNFTError, events::*, metadata::{pda_creator_address, verify_collection_nft}, state::*
+};
+
+// for collections created before the vault inventory existed
+pub fn process_initialize_vault_inventory(ctx: Context<InitializeVaultInventory>) -> Result<()> {
+    let mut vault_inventory = ctx.accounts.vault_inventory.load_init()?;
+    vault_inventory.collection_state = ctx.accounts.collection_state.key();
+    vault_inventory.bump = ctx.bumps.vault_inventory;
+
+    Ok(())
+}
+
+// records an NFT that reached the vault without going through redeem
+pub fn process_sync_vault_inventory(ctx: Context<SyncVaultInventory>) -> Result<()> {
+    let collection_state = &mut ctx.accounts.collection_state;
+
+    let (token_id, _) = verify_collection_nft(
+        &ctx.accounts.nft_metadata.to_account_info(),
+        collection_state,
+        &pda_creator_address(&collection_state.collection_mint),
+    )?;
+    ctx.accounts.vault_inventory.load_mut()?.insert(token_id)?;
+
+    collection_state.vault_nft_count = collection_state.vault_nft_count
+        .checked_add(1)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+
+    emit!(VaultInventorySynced {
+        collection_mint: collection_state.collection_mint,
+        nft_mint: ctx.accounts.nft_mint.key(),
+        token_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use anchor_lang::prelude::*;
 use crate::{
+    constants::{MAX_MINT_BATCH_SIZE, MINT_BATCH_ACCOUNTS, PAUSE_MINT, PHASE_GATE_OPEN},
     state::*,
     error::*,
-    utils::process_payment,
+    utils::{latest_slot_hash, process_payment},
 };
 
-use anchor_spl::token::Token;
+use anchor_spl::token::{Token, TokenAccount};
 use mpl_token_metadata::instructions::{
     CreateMetadataAccountV3, 
     CreateMetadataAccountV3InstructionArgs,
@@ -14,64 +15,347 @@ use mpl_token_metadata::instructions::{
     CreateMasterEditionV3InstructionArgs,
 };
 
-use solana_program::pubkey::Pubkey;
 use crate::{events::*, metadata::*};
 
 pub const METADATA_PROGRAM_PUBKEY: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
 
-pub fn process_mint(ctx: Context<MintNFT>) -> Result<()> {
-    // Check if collection is paused
-    ctx.accounts.collection_state.check_not_paused()?;
+pub fn process_mint(ctx: Context<MintNFT>, max_payment: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let phase = match MintPhaseConfig::load_optional(&ctx.accounts.mint_phase_config)? {
+        Some(config) => config.current_phase(now, PHASE_GATE_OPEN)?,
+        None => None,
+    };
+    let mint_price = ctx.accounts.collection_state.mint_price;
+    let price = match &phase {
+        Some((_, phase)) => phase.effective_price(mint_price)?,
+        None => mint_price,
+    };
+
+    execute_mint(ctx.accounts, &ctx.bumps, price, max_payment, phase.as_ref())
+}
+
+// pay `price` into the vault and mint one NFT, counted in the recipient's mint record
+pub fn execute_mint(
+    accounts: &mut MintNFT,
+    bumps: &MintNFTBumps,
+    price: u64,
+    max_payment: u64,
+    phase: Option<&(usize, MintPhase)>,
+) -> Result<()> {
+    // Check if protocol or collection is paused
+    accounts.program_state.check_entry_allowed()?;
+    accounts.collection_state.check_not_paused(PAUSE_MINT)?;
 
     // Verify payment token matches
     require!(
-        ctx.accounts.collection_state.payment_token_mint == ctx.accounts.payer_token_account.mint,
+        accounts.collection_state.payment_token_mint == accounts.payer_token_account.mint,
         InterchangeableNFTError::InvalidPaymentToken
     );
+    accounts.collection_state.validate_max_payment(price, max_payment)?;
+    accounts.collection_state.check_public_supply(1)?;
+
+    accounts.mint_record.record_mints(
+        accounts.collection_state.key(),
+        accounts.recipient.key(),
+        bumps.mint_record,
+        1,
+        phase,
+        accounts.collection_state.max_mints_per_wallet,
+    )?;
 
     process_payment(
-        &ctx.accounts.token_program,
-        &ctx.accounts.payer_token_account,
-        &ctx.accounts.vault_token_account,
-        &ctx.accounts.payer,
-        ctx.accounts.collection_state.mint_price,
+        &accounts.token_program,
+        &accounts.payer_token_account,
+        &accounts.vault_token_account,
+        &accounts.payer,
+        price,
     )?;
     
-    mint_single_nft(
-        &mut ctx.accounts.collection_state,
-        &ctx.accounts.payer,
-        ctx.accounts.nft_mint.to_account_info(),
-        ctx.accounts.metadata.to_account_info(),
-        ctx.accounts.edition.to_account_info(),
-        &ctx.accounts.token_program,
-        &ctx.accounts.system_program,
-        &ctx.accounts.rent,
-        ctx.accounts.collection_metadata.to_account_info(),
-        ctx.accounts.collection_master_edition.to_account_info(),
-        ctx.accounts.collection_authority_record.to_account_info(),
-        ctx.accounts.collection_mint.to_account_info(),
-        ctx.accounts.pda_creator.to_account_info(),
-        ctx.accounts.token_metadata_program.to_account_info(),
-    )
+    let mint_accounts = CollectionMintAccounts {
+        payer: &accounts.payer,
+        token_program: &accounts.token_program,
+        system_program: &accounts.system_program,
+        rent: &accounts.rent,
+        collection_metadata: accounts.collection_metadata.to_account_info(),
+        collection_master_edition: accounts.collection_master_edition.to_account_info(),
+        collection_authority_record: accounts.collection_authority_record.to_account_info(),
+        collection_mint: accounts.collection_mint.to_account_info(),
+        pda_creator: accounts.pda_creator.to_account_info(),
+        pda_creator_bump: bumps.pda_creator,
+        metadata_program: accounts.token_metadata_program.to_account_info(),
+        token_registry: accounts.token_registry.as_ref(),
+        id_pool: accounts.id_pool.as_ref(),
+        slot_hashes: accounts.slot_hashes.as_ref(),
+    };
+    let (token_id, metadata_id) = mint_single_nft(
+        &mut accounts.collection_state,
+        &mint_accounts,
+        accounts.nft_mint.to_account_info(),
+        accounts.metadata.to_account_info(),
+        accounts.edition.to_account_info(),
+    )?;
+
+    emit!(NFTMinted {
+        minter: accounts.payer.key(),
+        recipient: accounts.recipient.key(),
+        collection_mint: accounts.collection_state.collection_mint,
+        token_id,
+        metadata_id,
+        mint: accounts.nft_mint.key(),
+        amount: price,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Mints `count` NFTs for a single payment of `price * count`.
+// remaining_accounts holds one group of
+// (nft_mint, metadata, edition, nft_token_account) per NFT, the token
+// accounts belong to the recipient.
+pub fn process_mint_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MintBatch<'info>>,
+    count: u8,
+    max_payment: u64,
+) -> Result<()> {
+    ctx.accounts.program_state.check_entry_allowed()?;
+    ctx.accounts.collection_state.check_not_paused(PAUSE_MINT)?;
+    ctx.accounts.collection_state.check_public_supply(count as u64)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let phase = match MintPhaseConfig::load_optional(&ctx.accounts.mint_phase_config)? {
+        Some(config) => config.current_phase(now, PHASE_GATE_OPEN)?,
+        None => None,
+    };
+
+    let collection_state = &ctx.accounts.collection_state;
+    let price = match &phase {
+        Some((_, phase)) => phase.effective_price(collection_state.mint_price)?,
+        None => collection_state.mint_price,
+    };
+
+    ctx.accounts.mint_record.record_mints(
+        collection_state.key(),
+        ctx.accounts.recipient.key(),
+        ctx.bumps.mint_record,
+        count as u32,
+        phase.as_ref(),
+        collection_state.max_mints_per_wallet,
+    )?;
+
+    let batch = BatchMintAccounts {
+        collection_state: &mut ctx.accounts.collection_state,
+        payer_token_account: &ctx.accounts.payer_token_account,
+        vault_token_account: &ctx.accounts.vault_token_account,
+        recipient: ctx.accounts.recipient.key(),
+        mint_accounts: CollectionMintAccounts {
+            payer: &ctx.accounts.payer,
+            token_program: &ctx.accounts.token_program,
+            system_program: &ctx.accounts.system_program,
+            rent: &ctx.accounts.rent,
+            collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+            collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+            collection_authority_record: ctx.accounts.collection_authority_record.to_account_info(),
+            collection_mint: ctx.accounts.collection_mint.to_account_info(),
+            pda_creator: ctx.accounts.pda_creator.to_account_info(),
+            pda_creator_bump: ctx.bumps.pda_creator,
+            metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            token_registry: ctx.accounts.token_registry.as_ref(),
+            id_pool: ctx.accounts.id_pool.as_ref(),
+            slot_hashes: ctx.accounts.slot_hashes.as_ref(),
+        },
+    };
+    execute_mint_batch(batch, ctx.remaining_accounts, count, price, max_payment)
+}
+
+// Mints `count` reserved NFTs to the recipient. The authority deposits
+// mint_price per NFT into the vault, so reserved NFTs are backed and
+// redeemable like any other.
+pub fn process_authority_mint<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AuthorityMint<'info>>,
+    count: u8,
+) -> Result<()> {
+    ctx.accounts.program_state.check_entry_allowed()?;
+    ctx.accounts.collection_state.check_not_paused(PAUSE_MINT)?;
+
+    let collection_state = &mut ctx.accounts.collection_state;
+    collection_state.reserved_minted = collection_state.reserved_minted
+        .checked_add(count as u64)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+    require!(
+        collection_state.reserved_minted <= collection_state.reserved_supply,
+        InterchangeableNFTError::ReservedSupplyExceeded
+    );
+
+    let price = collection_state.mint_price;
+    let batch = BatchMintAccounts {
+        collection_state: &mut ctx.accounts.collection_state,
+        payer_token_account: &ctx.accounts.authority_token_account,
+        vault_token_account: &ctx.accounts.vault_token_account,
+        recipient: ctx.accounts.recipient.key(),
+        mint_accounts: CollectionMintAccounts {
+            payer: &ctx.accounts.authority,
+            token_program: &ctx.accounts.token_program,
+            system_program: &ctx.accounts.system_program,
+            rent: &ctx.accounts.rent,
+            collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+            collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+            collection_authority_record: ctx.accounts.collection_authority_record.to_account_info(),
+            collection_mint: ctx.accounts.collection_mint.to_account_info(),
+            pda_creator: ctx.accounts.pda_creator.to_account_info(),
+            pda_creator_bump: ctx.bumps.pda_creator,
+            metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            token_registry: ctx.accounts.token_registry.as_ref(),
+            id_pool: ctx.accounts.id_pool.as_ref(),
+            slot_hashes: ctx.accounts.slot_hashes.as_ref(),
+        },
+    };
+    execute_mint_batch(batch, ctx.remaining_accounts, count, price, u64::MAX)
+}
+
+// what execute_mint_batch needs from MintBatch or AuthorityMint
+struct BatchMintAccounts<'a, 'info> {
+    collection_state: &'a mut Account<'info, CollectionState>,
+    payer_token_account: &'a Account<'info, TokenAccount>,
+    vault_token_account: &'a Account<'info, TokenAccount>,
+    recipient: Pubkey,
+    mint_accounts: CollectionMintAccounts<'a, 'info>,
+}
+
+fn execute_mint_batch<'info>(
+    batch: BatchMintAccounts<'_, 'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    count: u8,
+    price: u64,
+    max_payment: u64,
+) -> Result<()> {
+    require!(
+        count > 0 && count <= MAX_MINT_BATCH_SIZE,
+        InterchangeableNFTError::InvalidBatchSize
+    );
+    require!(
+        remaining_accounts.len() == count as usize * MINT_BATCH_ACCOUNTS,
+        InterchangeableNFTError::InvalidRemainingAccounts
+    );
+
+    let BatchMintAccounts {
+        collection_state,
+        payer_token_account,
+        vault_token_account,
+        recipient,
+        mint_accounts,
+    } = batch;
+
+    let end_token_id = collection_state.next_token_id
+        .checked_add(count as u64)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+    require!(
+        end_token_id <= collection_state.max_supply,
+        InterchangeableNFTError::NoAvailableNFTs
+    );
+
+    let total_price = price
+        .checked_mul(count as u64)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+    collection_state.validate_max_payment(total_price, max_payment)?;
+
+    process_payment(
+        mint_accounts.token_program,
+        payer_token_account,
+        vault_token_account,
+        mint_accounts.payer,
+        total_price,
+    )?;
+
+    let first_token_id = collection_state.next_token_id;
+    let mut mints = Vec::with_capacity(count as usize);
+    let mut metadata_ids = Vec::with_capacity(count as usize);
+
+    for group in remaining_accounts.chunks(MINT_BATCH_ACCOUNTS) {
+        let nft_mint = &group[0];
+        let metadata = &group[1];
+        let edition = &group[2];
+        let nft_token_account = &group[3];
+
+        // the recipient must already hold the single token of the new mint
+        let token_account = Account::<TokenAccount>::try_from(nft_token_account)?;
+        require!(
+            token_account.mint == nft_mint.key() && token_account.owner == recipient,
+            InterchangeableNFTError::InvalidRemainingAccounts
+        );
+
+        let (_, metadata_id) = mint_single_nft(
+            collection_state,
+            &mint_accounts,
+            nft_mint.clone(),
+            metadata.clone(),
+            edition.clone(),
+        )?;
+        mints.push(nft_mint.key());
+        metadata_ids.extend(metadata_id);
+    }
+
+    emit!(NFTBatchMinted {
+        minter: mint_accounts.payer.key(),
+        recipient,
+        collection_mint: collection_state.collection_mint,
+        first_token_id,
+        last_token_id: collection_state.next_token_id - 1,
+        mints,
+        metadata_ids,
+        amount: total_price,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
 }
 
 
+// accounts shared by every NFT minted in one instruction
+pub struct CollectionMintAccounts<'a, 'info> {
+    pub payer: &'a Signer<'info>,
+    pub token_program: &'a Program<'info, Token>,
+    pub system_program: &'a Program<'info, System>,
+    pub rent: &'a Sysvar<'info, Rent>,
+    pub collection_metadata: AccountInfo<'info>,
+    pub collection_master_edition: AccountInfo<'info>,
+    pub collection_authority_record: AccountInfo<'info>,
+    pub collection_mint: AccountInfo<'info>,
+    pub pda_creator: AccountInfo<'info>,
+    pub pda_creator_bump: u8,
+    pub metadata_program: AccountInfo<'info>,
+    // set when the collection has a token registry
+    pub token_registry: Option<&'a AccountLoader<'info, TokenRegistry>>,
+    // set when the collection shuffles metadata ids
+    pub id_pool: Option<&'a AccountLoader<'info, IdPool>>,
+    pub slot_hashes: Option<&'a UncheckedAccount<'info>>,
+}
+
+// returns the token id and, once revealed, its metadata id
 pub fn mint_single_nft<'info>(
     collection_state: &mut Account<'info, CollectionState>,
-    payer: &Signer<'info>,
+    accounts: &CollectionMintAccounts<'_, 'info>,
     nft_mint: AccountInfo<'info>,
     metadata: AccountInfo<'info>,
     edition: AccountInfo<'info>,
-    token_program: &Program<'info, Token>,
-    system_program: &Program<'info, System>,
-    rent: &Sysvar<'info, Rent>,
-    collection_metadata: AccountInfo<'info>,
-    collection_master_edition: AccountInfo<'info>,
-    collection_authority_record: AccountInfo<'info>,
-    collection_mint: AccountInfo<'info>,
-    pda_creator: AccountInfo<'info>,
-    metadata_program: AccountInfo<'info>,
-) -> Result<()> {
+) -> Result<(u64, Option<u64>)> {
+    let CollectionMintAccounts {
+        payer,
+        token_program,
+        system_program,
+        rent,
+        collection_metadata,
+        collection_master_edition,
+        collection_authority_record,
+        collection_mint,
+        pda_creator,
+        pda_creator_bump,
+        metadata_program,
+        token_registry,
+        id_pool,
+        slot_hashes,
+    } = accounts;
+
     msg!("=== Starting mint single NFT ===");
 
     // Check if exceeds max supply
@@ -82,36 +366,32 @@ pub fn mint_single_nft<'info>(
     msg!("Creating new NFT, token_id: {}", collection_state.next_token_id);
     let token_id = collection_state.next_token_id;
 
-    // Calculate PDA seeds and bump
+    // PDA seeds, bump is checked by the accounts constraint
     let collection_mint_key = collection_mint.key();
-    let (pda_creator_calc, bump) = Pubkey::find_program_address(
-        &[
-            crate::ID.as_ref(),
-            collection_mint_key.as_ref(),
-            b"pda_creator",
-        ],
-        &crate::ID
-    );
-
     let seeds = &[
         crate::ID.as_ref(),
         collection_mint_key.as_ref(),
         b"pda_creator" as &[u8],
-        &[bump],
+        &[*pda_creator_bump],
     ];
-
-    // Compare pda_creator is correct
-    require!(
-        pda_creator_calc == pda_creator.key(),
-        InterchangeableNFTError::PubkeyMismatch
-    );
     
     // 1. Create NFT Metadata
     msg!("Creating NFT metadata");
+    // shuffled ids minted after the reveal are drawn from this mint's slot
+    let entropy = if collection_state.id_pool != Pubkey::default() && !collection_state.is_pre_reveal() {
+        let slot_hashes = slot_hashes.ok_or(InterchangeableNFTError::InvalidSlotHashes)?;
+        latest_slot_hash(&slot_hashes.to_account_info())?
+    } else {
+        [0; 32]
+    };
+    let mut id_pool = id_pool.map(|id_pool| id_pool.load_mut()).transpose()?;
+    let metadata_id = collection_state.metadata_id(token_id, id_pool.as_deref_mut(), &entropy)?;
+    drop(id_pool);
+    let uri = collection_state.token_uri(metadata_id);
     let metadata_data = create_nft_metadata_data(
         format!("{} #{}", collection_state.name, token_id),
         collection_state.symbol.clone(),
-        format!("{}/{}", collection_state.base_uri, token_id),
+        uri,
         collection_state.royalty_config.basis_points,
         pda_creator.key(),
         collection_state.royalty_config.receiver,
@@ -218,17 +498,13 @@ pub fn mint_single_nft<'info>(
         &[seeds],
     )?;
 
-    // 4. Send minted event
-    emit!(NFTMinted {
-        minter: payer.key(),
-        collection_mint: collection_state.collection_mint,
-        token_id: token_id,
-        mint: nft_mint.key(),
-        amount: collection_state.mint_price,
-        timestamp: Clock::get()?.unix_timestamp,
-    });
-    
+    // 4. Record token_id -> mint
+    if collection_state.token_registry != Pubkey::default() {
+        let token_registry = token_registry.ok_or(InterchangeableNFTError::InvalidTokenRegistry)?;
+        token_registry.load_mut()?.register(token_id, nft_mint.key())?;
+    }
+
     collection_state.next_token_id += 1;
-    Ok(())
+    Ok((token_id, metadata_id))
 
 }
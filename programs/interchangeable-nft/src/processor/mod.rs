@@ -3,9 +3,25 @@ pub mod mint;
 pub mod redeem;
 pub mod admin;
 pub mod pull;
+pub mod protocol;
+pub mod treasury;
+pub mod inventory;
+pub mod registry;
+pub mod random;
+pub mod swap;
+pub mod phase;
+pub mod reveal;
 
 pub use initialize::*;
 pub use mint::*;
 pub use redeem::*;
 pub use admin::*;
-pub use pull::*;
\ No newline at end of file
+pub use pull::*;
+pub use protocol::*;
+pub use treasury::*;
+pub use inventory::*;
+pub use registry::*;
+pub use random::*;
+pub use swap::*;
+pub use phase::*;
+pub use reveal::*;
\ No newline at end of file
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PHASE_GATE_ALLOWLIST, PHASE_GATE_TOKEN, PHASE_GATE_TOKEN_ONCE},
+    error::InterchangeableNFTError,
+    events::*,
+    metadata::verify_collection_membership,
+    processor::mint::execute_mint,
+    state::*,
+    utils::{allowlist_leaf, verify_merkle_proof},
+};
+
+pub fn process_set_mint_phase(
+    ctx: Context<SetMintPhase>,
+    merkle_root: [u8; 32],
+    allowlist_only: bool,
+) -> Result<()> {
+    let mint_phase_config = &mut ctx.accounts.mint_phase_config;
+    // a schedule carries its own allowlist phases, see set_mint_phases
+    require!(
+        mint_phase_config.phases.is_empty(),
+        InterchangeableNFTError::MintPhasesScheduled
+    );
+    mint_phase_config.collection_state = ctx.accounts.admin.collection_state.key();
+    mint_phase_config.merkle_root = merkle_root;
+    mint_phase_config.allowlist_only = allowlist_only;
+    mint_phase_config.bump = ctx.bumps.mint_phase_config;
+
+    emit!(MintPhaseUpdated {
+        collection_mint: ctx.accounts.admin.collection_state.collection_mint,
+        merkle_root,
+        allowlist_only,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_set_mint_phases(ctx: Context<SetMintPhases>, phases: Vec<MintPhase>) -> Result<()> {
+    MintPhaseConfig::validate_phases(&phases)?;
+
+    let phase_count = phases.len() as u8;
+    let mint_phase_config = &mut ctx.accounts.mint_phase_config;
+    mint_phase_config.collection_state = ctx.accounts.admin.collection_state.key();
+    mint_phase_config.bump = ctx.bumps.mint_phase_config;
+    // allowlists move into PHASE_GATE_ALLOWLIST phases, clearing the
+    // schedule must not bring back an old set_mint_phase allowlist
+    mint_phase_config.merkle_root = [0; 32];
+    mint_phase_config.allowlist_only = false;
+    mint_phase_config.phases = phases;
+
+    emit!(MintPhasesUpdated {
+        collection_mint: ctx.accounts.admin.collection_state.collection_mint,
+        phase_count,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// `quota` and `price` must match the recipient's allowlist leaf;
+// quota 0 is unlimited and price 0 falls back to the phase or collection price.
+// With phases scheduled the quota applies per phase.
+pub fn process_mint_allowlisted(
+    ctx: Context<MintAllowlisted>,
+    proof: Vec<[u8; 32]>,
+    quota: u32,
+    price: u64,
+    max_payment: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let phase = ctx.accounts.mint_phase_config.current_phase(now, PHASE_GATE_ALLOWLIST)?;
+
+    let recipient = ctx.accounts.mint.recipient.key();
+    let leaf = allowlist_leaf(&recipient, quota, price);
+    let merkle_root = ctx.accounts.mint_phase_config.allowlist_root(phase.as_ref().map(|(_, phase)| phase));
+    require!(
+        verify_merkle_proof(&proof, &merkle_root, leaf),
+        InterchangeableNFTError::InvalidAllowlistProof
+    );
+
+    // every NFT must stay backed by mint_price in the vault
+    let mint_price = ctx.accounts.mint.collection_state.mint_price;
+    let price = match (price, &phase) {
+        (0, Some((_, phase))) => phase.effective_price(mint_price)?,
+        (0, None) => mint_price,
+        (price, _) => price,
+    };
+    require!(price >= mint_price, InterchangeableNFTError::InvalidAllowlistPrice);
+
+    let used = ctx.accounts.mint.mint_record.used(phase.as_ref());
+    require!(
+        quota == 0 || used < quota,
+        InterchangeableNFTError::AllowlistQuotaExceeded
+    );
+
+    execute_mint(&mut ctx.accounts.mint, &ctx.bumps.mint, price, max_payment, phase.as_ref())
+}
+
+// mint in a token-gated phase for a recipient holding an NFT of the phase's
+// gate collection
+pub fn process_mint_token_gated(ctx: Context<MintTokenGated>, max_payment: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let (index, phase) = ctx.accounts.mint_phase_config
+        .current_phase(now, PHASE_GATE_TOKEN)?
+        .ok_or(InterchangeableNFTError::MintPhaseClosed)?;
+
+    verify_collection_membership(
+        &ctx.accounts.gate_nft_metadata.to_account_info(),
+        &phase.gate_collection,
+    )?;
+
+    if phase.gate == PHASE_GATE_TOKEN_ONCE {
+        let gate_record = ctx.accounts.gate_record.as_mut()
+            .ok_or(InterchangeableNFTError::MissingGateRecord)?;
+        require!(
+            gate_record.used_by == Pubkey::default(),
+            InterchangeableNFTError::GateNFTAlreadyUsed
+        );
+        gate_record.collection_state = ctx.accounts.mint.collection_state.key();
+        gate_record.gate_mint = ctx.accounts.gate_nft_mint.key();
+        gate_record.used_by = ctx.accounts.mint.recipient.key();
+        gate_record.bump = ctx.bumps.gate_record.unwrap_or_default();
+    }
+
+    let price = phase.effective_price(ctx.accounts.mint.collection_state.mint_price)?;
+    execute_mint(&mut ctx.accounts.mint, &ctx.bumps.mint, price, max_payment, Some(&(index, phase)))
+}
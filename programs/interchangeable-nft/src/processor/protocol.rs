@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::{
+    events::*,
+    state::*,
+};
+
+pub fn process_initialize_program_state(
+    ctx: Context<InitializeProgramState>,
+    params: ProgramStateParams,
+) -> Result<()> {
+    let program_state = &mut ctx.accounts.program_state;
+    program_state.admin = ctx.accounts.admin.key();
+    program_state.bump = ctx.bumps.program_state;
+    program_state.apply(params)?;
+
+    emit_program_state_updated(program_state)
+}
+
+pub fn process_update_program_state(
+    ctx: Context<ProtocolAdmin>,
+    params: ProgramStateParams,
+) -> Result<()> {
+    let program_state = &mut ctx.accounts.program_state;
+    program_state.apply(params)?;
+
+    emit_program_state_updated(program_state)
+}
+
+pub fn process_set_protocol_pause(
+    ctx: Context<ProtocolAdmin>,
+    paused: bool,
+    redeem_only: bool,
+) -> Result<()> {
+    let program_state = &mut ctx.accounts.program_state;
+    program_state.paused = paused;
+    program_state.redeem_only = redeem_only;
+
+    emit!(ProtocolPauseChanged {
+        admin: ctx.accounts.admin.key(),
+        paused,
+        redeem_only,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn emit_program_state_updated(program_state: &ProgramState) -> Result<()> {
+    emit!(ProgramStateUpdated {
+        admin: program_state.admin,
+        fee_receiver: program_state.fee_receiver,
+        default_redeem_fee: program_state.default_redeem_fee,
+        max_redeem_fee: program_state.max_redeem_fee,
+        paused: program_state.paused,
+        allowed_payment_mints: program_state.allowed_payment_mints.clone(),
+        protocol_fee_bps: program_state.protocol_fee_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
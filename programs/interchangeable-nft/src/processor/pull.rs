@@ -1,51 +1,89 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, TransferChecked};
 use crate::{
-    error::InterchangeableNFTError, events::*, state::*, utils::process_payment
+    constants::PAUSE_PULL, error::InterchangeableNFTError, events::*, metadata::{pda_creator_address, verify_collection_nft}, state::*, utils::process_payment
 };
 
-pub fn pull_nft(ctx: Context<PullNft>) -> Result<()> {
+pub fn pull_nft(ctx: Context<PullNft>, max_payment: u64) -> Result<()> {
     msg!("Starting pull NFT instruction");
 
-    ctx.accounts.collection_state.check_not_paused()?;
+    execute_pull(ctx.accounts, None, max_payment)
+}
+
+pub fn pull_nft_by_id(ctx: Context<PullNftById>, token_id: u64, max_payment: u64) -> Result<()> {
+    msg!("Starting pull NFT by id instruction, token_id: {}", token_id);
+
+    // Resolve the mint from the on-chain registry
+    let registered_mint = ctx.accounts.token_registry.load()?.mint_of(token_id)?;
+    require!(
+        registered_mint == ctx.accounts.pull.nft_mint.key(),
+        InterchangeableNFTError::InvalidTokenId
+    );
+
+    execute_pull(&mut ctx.accounts.pull, Some(token_id), max_payment)
+}
+
+fn execute_pull(
+    accounts: &mut PullNft,
+    expected_token_id: Option<u64>,
+    max_payment: u64,
+) -> Result<()> {
+    accounts.program_state.check_entry_allowed()?;
+    accounts.collection_state.check_not_paused(PAUSE_PULL)?;
+    accounts.collection_state.check_vault_available()?;
+
+    // Only NFTs of this collection that are recorded in the vault can be pulled
+    let (token_id, _) = verify_collection_nft(
+        &accounts.nft_metadata.to_account_info(),
+        &accounts.collection_state,
+        &pda_creator_address(&accounts.collection_state.collection_mint),
+    )?;
+    if let Some(expected_token_id) = expected_token_id {
+        require!(token_id == expected_token_id, InterchangeableNFTError::InvalidTokenId);
+    }
+    accounts.vault_inventory.load_mut()?.remove(token_id)?;
 
     // Verify payment token matches
     require!(
-        ctx.accounts.collection_state.payment_token_mint == ctx.accounts.payer_token_account.mint,
+        accounts.collection_state.payment_token_mint == accounts.payer_token_account.mint,
         InterchangeableNFTError::InvalidPaymentToken
     );
+    accounts.collection_state.validate_max_payment(
+        accounts.collection_state.mint_price,
+        max_payment,
+    )?;
 
     process_payment(
-        &ctx.accounts.token_program,
-        &ctx.accounts.payer_token_account,
-        &ctx.accounts.vault_token_account,
-        &ctx.accounts.payer,
-        ctx.accounts.collection_state.mint_price,
+        &accounts.token_program,
+        &accounts.payer_token_account,
+        &accounts.vault_token_account,
+        &accounts.payer,
+        accounts.collection_state.mint_price,
     )?;
     
-    let collection_state = &mut ctx.accounts.collection_state;
-    let nft_mint = &ctx.accounts.nft_mint;
+    let collection_state = &mut accounts.collection_state;
+    let nft_mint = &accounts.nft_mint;
     
     // Get collection state PDA seeds
     let signer_seeds: &[&[&[u8]]] = &[&[
         b"collection",
-        collection_state.authority.as_ref(),
+        collection_state.seed_authority.as_ref(),
         collection_state.collection_mint.as_ref(),
         &[collection_state.bump],
     ]];
 
     msg!("Executing NFT transfer");
-    msg!("From: {}", ctx.accounts.vault_nft_account.key());
-    msg!("To: {}", ctx.accounts.payer_nft_account.key());
+    msg!("From: {}", accounts.vault_nft_account.key());
+    msg!("To: {}", accounts.payer_nft_account.key());
     msg!("Authority: {}", collection_state.key());
 
     // Execute transfer
     let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
+        accounts.token_program.to_account_info(),
         TransferChecked {
-            from: ctx.accounts.vault_nft_account.to_account_info(),
-            mint: ctx.accounts.nft_mint.to_account_info(),
-            to: ctx.accounts.payer_nft_account.to_account_info(),
+            from: accounts.vault_nft_account.to_account_info(),
+            mint: accounts.nft_mint.to_account_info(),
+            to: accounts.payer_nft_account.to_account_info(),
             authority: collection_state.to_account_info(),
         },
         signer_seeds,
@@ -54,11 +92,15 @@ pub fn pull_nft(ctx: Context<PullNft>) -> Result<()> {
     token::transfer_checked(transfer_ctx, 1, 0)?;  // 1 token, 0 decimals for NFT
     msg!("NFT transfer completed");
 
+    // NFTs redeemed before the counter existed were never counted
+    collection_state.vault_nft_count = collection_state.vault_nft_count.saturating_sub(1);
+
     // Send pull event
     emit!(NFTPull {
-        puller: ctx.accounts.payer.key(),
+        puller: accounts.payer.key(),
         collection_mint: collection_state.collection_mint,
         mint: nft_mint.key(),
+        token_id,
         amount: collection_state.mint_price,
         timestamp: Clock::get()?.unix_timestamp,
     });
@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TransferChecked};
+use solana_program::hash::hash;
+
+use crate::{
+    constants::PAUSE_PULL, error::InterchangeableNFTError, events::*, state::*,
+    utils::{find_slot_hash, process_payment, random_u64},
+};
+
+// Random pulls are settled over two transactions. The commit pays for the
+// pull and records hash(seed) and the vault inventory generation; the reveal
+// mixes the seed with the hash of the commit slot, which the payer cannot
+// know when signing the commit, and draws from the unchanged inventory.
+pub fn process_commit_pull_random(
+    ctx: Context<CommitPullRandom>,
+    seed_hash: [u8; 32],
+    max_payment: u64,
+) -> Result<()> {
+    ctx.accounts.program_state.check_entry_allowed()?;
+    ctx.accounts.collection_state.check_not_paused(PAUSE_PULL)?;
+    ctx.accounts.collection_state.check_vault_available()?;
+    // the reveal resolves the drawn token_id through the registry
+    require!(
+        ctx.accounts.collection_state.token_registry != Pubkey::default(),
+        InterchangeableNFTError::InvalidTokenRegistry
+    );
+
+    let amount = ctx.accounts.collection_state.mint_price;
+    ctx.accounts.collection_state.validate_max_payment(amount, max_payment)?;
+    process_payment(
+        &ctx.accounts.token_program,
+        &ctx.accounts.payer_token_account,
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.payer,
+        amount,
+    )?;
+
+    let commit_slot = Clock::get()?.slot;
+    let commitment = &mut ctx.accounts.commitment;
+    commitment.collection_state = ctx.accounts.collection_state.key();
+    commitment.payer = ctx.accounts.payer.key();
+    commitment.seed_hash = seed_hash;
+    commitment.commit_slot = commit_slot;
+    commitment.amount = amount;
+    commitment.bump = ctx.bumps.commitment;
+    commitment.inventory_generation = ctx.accounts.vault_inventory.load()?.generation();
+
+    // reserve one vault NFT for the reveal
+    let collection_state = &mut ctx.accounts.collection_state;
+    collection_state.pending_random_pulls = collection_state.pending_random_pulls
+        .checked_add(1)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+
+    emit!(RandomPullCommitted {
+        puller: ctx.accounts.payer.key(),
+        collection_mint: collection_state.collection_mint,
+        commit_slot,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_reveal_pull_random(ctx: Context<RevealPullRandom>, seed: [u8; 32]) -> Result<()> {
+    ctx.accounts.program_state.check_not_paused()?;
+
+    let commitment = &ctx.accounts.commitment;
+    require!(
+        hash(&seed).to_bytes() == commitment.seed_hash,
+        InterchangeableNFTError::InvalidCommitmentSeed
+    );
+    require!(
+        Clock::get()?.slot > commitment.commit_slot,
+        InterchangeableNFTError::CommitmentNotReady
+    );
+    let slot_hash = find_slot_hash(&ctx.accounts.slot_hashes, commitment.commit_slot)?
+        .ok_or(InterchangeableNFTError::CommitmentExpired)?;
+
+    // pick one of the NFTs in the vault; any redeem, pull or swap since the
+    // commit would let the payer steer the draw, so it must be cancelled instead
+    let token_id = {
+        let mut vault_inventory = ctx.accounts.vault_inventory.load_mut()?;
+        require!(
+            vault_inventory.generation() == commitment.inventory_generation,
+            InterchangeableNFTError::VaultInventoryChanged
+        );
+        let available = vault_inventory.len();
+        require!(available > 0, InterchangeableNFTError::NoAvailableNFTs);
+
+        let random = random_u64(&[&slot_hash, &seed, ctx.accounts.payer.key.as_ref()]);
+        let token_id = vault_inventory
+            .nth(random % available)
+            .ok_or(InterchangeableNFTError::NoAvailableNFTs)?;
+        vault_inventory.remove(token_id)?;
+        token_id
+    };
+    msg!("Selected token_id: {}", token_id);
+
+    require!(
+        ctx.accounts.token_registry.load()?.mint_of(token_id)? == ctx.accounts.nft_mint.key(),
+        InterchangeableNFTError::InvalidTokenId
+    );
+
+    let collection_state = &mut ctx.accounts.collection_state;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"collection",
+        collection_state.seed_authority.as_ref(),
+        collection_state.collection_mint.as_ref(),
+        &[collection_state.bump],
+    ]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_nft_account.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            to: ctx.accounts.payer_nft_account.to_account_info(),
+            authority: collection_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer_checked(transfer_ctx, 1, 0)?;  // 1 token, 0 decimals for NFT
+
+    collection_state.vault_nft_count = collection_state.vault_nft_count.saturating_sub(1);
+    collection_state.pending_random_pulls = collection_state.pending_random_pulls.saturating_sub(1);
+
+    emit!(NFTRandomPull {
+        puller: ctx.accounts.payer.key(),
+        collection_mint: collection_state.collection_mint,
+        mint: ctx.accounts.nft_mint.key(),
+        token_id,
+        amount: ctx.accounts.commitment.amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// A commitment whose slot hash has left the SlotHashes window, or whose
+// vault inventory changed before the reveal, can no longer be revealed. An
+// expired commitment is refunded minus the redeem fee, so letting it lapse
+// is never cheaper than revealing and redeeming. A vault change is anyone's
+// doing, so that refund is in full.
+pub fn process_cancel_pull_random(ctx: Context<CancelPullRandom>) -> Result<()> {
+    ctx.accounts.program_state.check_not_paused()?;
+
+    let commitment = &ctx.accounts.commitment;
+    let expired = find_slot_hash(&ctx.accounts.slot_hashes, commitment.commit_slot)?.is_none()
+        && Clock::get()?.slot > commitment.commit_slot;
+    let inventory_changed =
+        ctx.accounts.vault_inventory.load()?.generation() != commitment.inventory_generation;
+    require!(
+        expired || inventory_changed,
+        InterchangeableNFTError::CommitmentNotExpired
+    );
+
+    let collection_state = &mut ctx.accounts.collection_state;
+    let redeem_fee = ctx.accounts.program_state.effective_redeem_fee(collection_state.redeem_fee);
+    let amount = ctx.accounts.commitment.amount;
+    let fee_amount = if inventory_changed {
+        0
+    } else {
+        amount
+            .checked_mul(redeem_fee as u64)
+            .ok_or(InterchangeableNFTError::MathOverflow)?
+            / 10000
+    };
+    let refund_amount = amount - fee_amount;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"collection",
+        collection_state.seed_authority.as_ref(),
+        collection_state.collection_mint.as_ref(),
+        &[collection_state.bump],
+    ]];
+
+    for (to, value) in [
+        (ctx.accounts.fee_receiver_token_account.to_account_info(), fee_amount),
+        (ctx.accounts.payer_token_account.to_account_info(), refund_amount),
+    ] {
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                to,
+                authority: collection_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer_checked(transfer_ctx, value, ctx.accounts.payment_token_mint.decimals)?;
+    }
+
+    collection_state.pending_random_pulls = collection_state.pending_random_pulls.saturating_sub(1);
+
+    emit!(RandomPullCancelled {
+        puller: ctx.accounts.payer.key(),
+        collection_mint: collection_state.collection_mint,
+        refund_amount,
+        fee_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,61 +1,49 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, TransferChecked};
+use anchor_spl::token::{self, TokenAccount, TransferChecked};
 
 use crate::{
-    error::InterchangeableNFTError, events::*, state::*
+    constants::{MAX_REDEEM_BATCH_SIZE, PAUSE_REDEEM, REDEEM_BATCH_ACCOUNTS}, error::InterchangeableNFTError, events::*, metadata::{pda_creator_address, verify_collection_nft}, state::*
 };
 
 pub fn process_redeem(ctx: Context<RedeemNFT>) -> Result<()> {
-    let collection_state = &mut ctx.accounts.collection_state;
-    
-    collection_state.check_not_paused()?;
+    let payer_token_account = ctx.accounts.payer_token_account.to_account_info();
 
+    execute_redeem(ctx.accounts, payer_token_account, None)
+}
 
-    // 0.1 verify NFT Metadata
-    let nft_metadata = mpl_token_metadata::accounts::Metadata::try_from(
-        &ctx.accounts.nft_metadata.to_account_info()
-    )?;
-    require!(nft_metadata.collection.is_some(), InterchangeableNFTError::InvalidCollectionNFT);
-    
-    let collection = nft_metadata.collection.as_ref().unwrap();
-    
-    // 0.2 verify Collection
-    require!(
-        collection.key == collection_state.collection_mint,
-        InterchangeableNFTError::InvalidCollectionNFT
-    );
-    require!(
-        collection.verified,
-        InterchangeableNFTError::UnverifiedCollection
-    );
+pub fn process_redeem_to(ctx: Context<RedeemTo>, min_amount_out: u64) -> Result<()> {
+    let recipient_token_account = ctx.accounts.recipient_token_account.to_account_info();
 
-    // 0.3 verify Creator
-    let creators = nft_metadata.creators.as_ref()
-        .ok_or(InterchangeableNFTError::InvalidNFTCreator)?;
+    execute_redeem(&mut ctx.accounts.redeem, recipient_token_account, Some(min_amount_out))
+}
+
+fn execute_redeem<'info>(
+    accounts: &mut RedeemNFT<'info>,
+    recipient_token_account: AccountInfo<'info>,
+    min_amount_out: Option<u64>,
+) -> Result<()> {
+    let collection_state = &mut accounts.collection_state;
     
-    let pda_creator = Pubkey::find_program_address(
-        &[
-            crate::ID.as_ref(),
-            collection_state.collection_mint.as_ref(),
-            b"pda_creator",
-        ],
-        &crate::ID
-    ).0;
+    accounts.program_state.check_not_paused()?;
+    collection_state.check_not_paused(PAUSE_REDEEM)?;
 
-    require!(
-        creators.iter().any(|c| c.address == pda_creator && c.verified),
-        InterchangeableNFTError::InvalidNFTCreator
-    );
+    // 0. verify NFT belongs to this collection
+    let (token_id, _) = verify_collection_nft(
+        &accounts.nft_metadata.to_account_info(),
+        collection_state,
+        &pda_creator_address(&collection_state.collection_mint),
+    )?;
+    accounts.vault_inventory.load_mut()?.insert(token_id)?;
 
     msg!("Transferring NFT from payer to vault");
     //  1. transfer Nft  transfer_checked
     let transfer_nft_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
+        accounts.token_program.to_account_info(),
         TransferChecked {
-            from: ctx.accounts.payer_nft_account.to_account_info(),
-            mint: ctx.accounts.nft_mint.to_account_info(),
-            to: ctx.accounts.vault_nft_account.to_account_info(),
-            authority: ctx.accounts.payer.to_account_info(),
+            from: accounts.payer_nft_account.to_account_info(),
+            mint: accounts.nft_mint.to_account_info(),
+            to: accounts.vault_nft_account.to_account_info(),
+            authority: accounts.payer.to_account_info(),
         },
     );
     token::transfer_checked(transfer_nft_ctx, 1, 0)?;
@@ -65,50 +53,67 @@ pub fn process_redeem(ctx: Context<RedeemNFT>) -> Result<()> {
     // 2. Execute all transfers
     let signer_seeds: &[&[&[u8]]] = &[&[
         b"collection",
-        collection_state.authority.as_ref(),
+        collection_state.seed_authority.as_ref(),
         collection_state.collection_mint.as_ref(),
         &[collection_state.bump],
     ]];
 
     // 3. Calculate amount
-    let (fee_amount, user_amount) = {
-        let fee_amount = collection_state.mint_price
-            .checked_mul(collection_state.redeem_fee as u64)
-            .unwrap()
-            .checked_div(10000)
-            .unwrap();
-        let user_amount = collection_state.mint_price
-            .checked_sub(fee_amount)
-            .unwrap();
-        (fee_amount, user_amount)
-    };
+    let redeem_fee = accounts.program_state.effective_redeem_fee(collection_state.redeem_fee);
+    let (fee_amount, user_amount) = collection_state.redeem_amounts(redeem_fee)?;
+    // price or fee may have changed since the user signed
+    if let Some(min_amount_out) = min_amount_out {
+        require!(user_amount >= min_amount_out, InterchangeableNFTError::SlippageExceeded);
+    }
 
     // 4. Transfer fee to specified account using transfer_checked
     {
+        let (collection_fee, protocol_fee) = accounts.program_state.split_fee(fee_amount)?;
+        if protocol_fee > 0 {
+            let protocol_fee_token_account = accounts.protocol_fee_token_account
+                .as_ref()
+                .ok_or(InterchangeableNFTError::InvalidFeeReceiver)?;
+            let protocol_fee_ctx = CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.vault_token_account.to_account_info(),
+                    mint: accounts.payment_token_mint.to_account_info(),
+                    to: protocol_fee_token_account.to_account_info(),
+                    authority: collection_state.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer_checked(
+                protocol_fee_ctx,
+                protocol_fee,
+                accounts.payment_token_mint.decimals
+            )?;
+        }
+
         let fee_transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.to_account_info(),
             TransferChecked {
-                from: ctx.accounts.vault_token_account.to_account_info(),
-                mint: ctx.accounts.payment_token_mint.to_account_info(),
-                to: ctx.accounts.fee_receiver_token_account.to_account_info(),
+                from: accounts.vault_token_account.to_account_info(),
+                mint: accounts.payment_token_mint.to_account_info(),
+                to: accounts.fee_receiver_token_account.to_account_info(),
                 authority: collection_state.to_account_info(),
             },
             signer_seeds,
         );
         token::transfer_checked(
             fee_transfer_ctx, 
-            fee_amount, 
-            ctx.accounts.payment_token_mint.decimals
+            collection_fee, 
+            accounts.payment_token_mint.decimals
         )?;
         msg!("Fee transfer completed: {} tokens", fee_amount);
 
         // 5. Transfer remaining tokens to user using transfer_checked
         let user_transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            accounts.token_program.to_account_info(),
             TransferChecked {
-                from: ctx.accounts.vault_token_account.to_account_info(),
-                mint: ctx.accounts.payment_token_mint.to_account_info(),
-                to: ctx.accounts.payer_token_account.to_account_info(),
+                from: accounts.vault_token_account.to_account_info(),
+                mint: accounts.payment_token_mint.to_account_info(),
+                to: recipient_token_account.clone(),
                 authority: collection_state.to_account_info(),
             },
             signer_seeds,
@@ -116,20 +121,184 @@ pub fn process_redeem(ctx: Context<RedeemNFT>) -> Result<()> {
         token::transfer_checked(
             user_transfer_ctx, 
             user_amount, 
-            ctx.accounts.payment_token_mint.decimals
+            accounts.payment_token_mint.decimals
         )?;
         msg!("User transfer completed: {} tokens", user_amount);
     };
 
+    collection_state.vault_nft_count = collection_state.vault_nft_count
+        .checked_add(1)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+
     // 6. Send redeem event
     emit!(NFTRedeemed {
-        redeemer: ctx.accounts.payer.key(),
+        redeemer: accounts.payer.key(),
         collection_mint: collection_state.collection_mint,
-        nft_mint: ctx.accounts.nft_mint.key(),
+        nft_mint: accounts.nft_mint.key(),
+        recipient_token_account: recipient_token_account.key(),
         amount: collection_state.mint_price,
         fee_amount,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+// Redeems several NFTs with one fee transfer and one user transfer.
+// remaining_accounts holds one group of
+// (nft_mint, nft_metadata, payer_nft_account, vault_nft_account) per NFT;
+// any invalid group fails the whole instruction.
+pub fn process_redeem_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RedeemBatch<'info>>,
+    count: u8,
+) -> Result<()> {
+    ctx.accounts.program_state.check_not_paused()?;
+    ctx.accounts.collection_state.check_not_paused(PAUSE_REDEEM)?;
+
+    require!(
+        count > 0 && count <= MAX_REDEEM_BATCH_SIZE,
+        InterchangeableNFTError::InvalidBatchSize
+    );
+    require!(
+        ctx.remaining_accounts.len() == count as usize * REDEEM_BATCH_ACCOUNTS,
+        InterchangeableNFTError::InvalidRemainingAccounts
+    );
+
+    let collection_state = &mut ctx.accounts.collection_state;
+    let payer_key = ctx.accounts.payer.key();
+    let pda_creator = pda_creator_address(&collection_state.collection_mint);
+    let mut nft_mints = Vec::with_capacity(count as usize);
+
+    // 0. verify and transfer every NFT to the vault
+    for group in ctx.remaining_accounts.chunks(REDEEM_BATCH_ACCOUNTS) {
+        let nft_mint = &group[0];
+        let nft_metadata = &group[1];
+        let payer_nft_account = &group[2];
+        let vault_nft_account = &group[3];
+
+        // only the metadata program writes accounts it owns, so a metadata
+        // account naming nft_mint is that mint's metadata PDA
+        require!(
+            nft_metadata.owner == &mpl_token_metadata::ID,
+            InterchangeableNFTError::InvalidRemainingAccounts
+        );
+        let (token_id, metadata) = verify_collection_nft(nft_metadata, collection_state, &pda_creator)?;
+        require!(
+            metadata.mint == nft_mint.key(),
+            InterchangeableNFTError::InvalidRemainingAccounts
+        );
+        ctx.accounts.vault_inventory.load_mut()?.insert(token_id)?;
+
+        let payer_token = Account::<TokenAccount>::try_from(payer_nft_account)?;
+        require!(
+            payer_token.mint == nft_mint.key() && payer_token.owner == payer_key,
+            InterchangeableNFTError::InvalidRemainingAccounts
+        );
+        let vault_token = Account::<TokenAccount>::try_from(vault_nft_account)?;
+        require!(
+            vault_token.mint == nft_mint.key() && vault_token.owner == collection_state.key(),
+            InterchangeableNFTError::InvalidRemainingAccounts
+        );
+
+        let transfer_nft_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: payer_nft_account.clone(),
+                mint: nft_mint.clone(),
+                to: vault_nft_account.clone(),
+                authority: ctx.accounts.payer.to_account_info(),
+            },
+        );
+        token::transfer_checked(transfer_nft_ctx, 1, 0)?;
+
+        nft_mints.push(nft_mint.key());
+    }
+
+    // 1. Calculate total amounts
+    let redeem_fee = ctx.accounts.program_state.effective_redeem_fee(collection_state.redeem_fee);
+    let (fee_amount, user_amount) = collection_state.redeem_amounts(redeem_fee)?;
+    let total_fee = fee_amount
+        .checked_mul(count as u64)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+    let total_user = user_amount
+        .checked_mul(count as u64)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"collection",
+        collection_state.seed_authority.as_ref(),
+        collection_state.collection_mint.as_ref(),
+        &[collection_state.bump],
+    ]];
+
+    // 2. Transfer fee, the protocol's share first
+    let (collection_fee, protocol_fee) = ctx.accounts.program_state.split_fee(total_fee)?;
+    if protocol_fee > 0 {
+        let protocol_fee_token_account = ctx.accounts.protocol_fee_token_account
+            .as_ref()
+            .ok_or(InterchangeableNFTError::InvalidFeeReceiver)?;
+        let protocol_fee_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                to: protocol_fee_token_account.to_account_info(),
+                authority: collection_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer_checked(
+            protocol_fee_ctx,
+            protocol_fee,
+            ctx.accounts.payment_token_mint.decimals
+        )?;
+    }
+
+    let fee_transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            mint: ctx.accounts.payment_token_mint.to_account_info(),
+            to: ctx.accounts.fee_receiver_token_account.to_account_info(),
+            authority: collection_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer_checked(
+        fee_transfer_ctx,
+        collection_fee,
+        ctx.accounts.payment_token_mint.decimals
+    )?;
+
+    // 3. Transfer remaining tokens to user
+    let user_transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            mint: ctx.accounts.payment_token_mint.to_account_info(),
+            to: ctx.accounts.payer_token_account.to_account_info(),
+            authority: collection_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer_checked(
+        user_transfer_ctx,
+        total_user,
+        ctx.accounts.payment_token_mint.decimals
+    )?;
+
+    collection_state.vault_nft_count = collection_state.vault_nft_count
+        .checked_add(count as u64)
+        .ok_or(InterchangeableNFTError::MathOverflow)?;
+
+    emit!(NFTBatchRedeemed {
+        redeemer: payer_key,
+        collection_mint: collection_state.collection_mint,
+        nft_mints,
+        amount: total_fee + total_user,
+        fee_amount: total_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use crate::{
+    error::InterchangeableNFTError, events::*, metadata::{pda_creator_address, verify_collection_nft}, state::*
+};
+
+pub fn process_initialize_token_registry(ctx: Context<InitializeTokenRegistry>) -> Result<()> {
+    let collection_state = &mut ctx.accounts.admin.collection_state;
+    require!(
+        collection_state.token_registry == Pubkey::default(),
+        InterchangeableNFTError::InvalidTokenRegistry
+    );
+
+    let mut token_registry = ctx.accounts.token_registry.load_init()?;
+    token_registry.collection_state = collection_state.key();
+    collection_state.token_registry = ctx.accounts.token_registry.key();
+
+    Ok(())
+}
+
+// switch a collection to shuffled metadata ids, only before the first mint
+// and before a delayed reveal is requested. Without a delayed reveal every
+// id is drawn at mint, so the seed is public and only opens the pool.
+pub fn process_initialize_id_pool(ctx: Context<InitializeIdPool>) -> Result<()> {
+    let collection_state = &mut ctx.accounts.admin.collection_state;
+    require!(
+        collection_state.id_pool == Pubkey::default() && collection_state.next_token_id == 0,
+        InterchangeableNFTError::InvalidIdPool
+    );
+    require!(
+        collection_state.reveal_slot == 0,
+        InterchangeableNFTError::RevealPending
+    );
+
+    let mut id_pool = ctx.accounts.id_pool.load_init()?;
+    id_pool.collection_state = collection_state.key();
+    if !collection_state.is_pre_reveal() {
+        id_pool.seed = hashv(&[
+            collection_state.key().as_ref(),
+            ctx.accounts.id_pool.key().as_ref(),
+        ])
+        .to_bytes();
+    }
+    collection_state.id_pool = ctx.accounts.id_pool.key();
+
+    Ok(())
+}
+
+// backfills NFTs minted before the collection had a registry
+pub fn process_register_nft(ctx: Context<RegisterNft>) -> Result<()> {
+    let collection_state = &ctx.accounts.collection_state;
+    let (token_id, _) = verify_collection_nft(
+        &ctx.accounts.nft_metadata.to_account_info(),
+        collection_state,
+        &pda_creator_address(&collection_state.collection_mint),
+    )?;
+    ctx.accounts.token_registry.load_mut()?.register(token_id, ctx.accounts.nft_mint.key())?;
+
+    emit!(NFTRegistered {
+        collection_mint: collection_state.collection_mint,
+        nft_mint: ctx.accounts.nft_mint.key(),
+        token_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
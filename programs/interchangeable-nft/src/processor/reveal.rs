@@ -0,0 +1,161 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::hash::{hash, hashv},
+};
+use mpl_token_metadata::{
+    instructions::{UpdateMetadataAccountV2, UpdateMetadataAccountV2InstructionArgs},
+    types::DataV2,
+};
+
+use crate::{
+    constants::MAX_URI_LENGTH,
+    error::InterchangeableNFTError,
+    events::*,
+    metadata::verify_collection_nft,
+    state::*,
+    utils::{find_slot_hash, random_u64},
+};
+
+// The reveal is settled over two transactions. reveal sets the final base
+// URI and opens the seed committed at initialize; settle_reveal, which anyone
+// can send, mixes the seed with the hash of the reveal slot, unknown when the
+// authority signs reveal. Metadata of already-minted NFTs is then moved over
+// by update_revealed_metadata.
+pub fn process_reveal(ctx: Context<Reveal>, base_uri: String, offset_seed: [u8; 32]) -> Result<()> {
+    let collection_state = &mut ctx.accounts.admin.collection_state;
+    require!(collection_state.is_pre_reveal(), InterchangeableNFTError::NotPreReveal);
+    require!(
+        !base_uri.is_empty() && base_uri.len() <= MAX_URI_LENGTH,
+        InterchangeableNFTError::InvalidBaseURI
+    );
+    require!(
+        hash(&offset_seed).to_bytes() == collection_state.offset_seed_hash,
+        InterchangeableNFTError::InvalidCommitmentSeed
+    );
+
+    // a pending reveal can only be replaced once its slot hash is gone
+    let slot = Clock::get()?.slot;
+    if collection_state.reveal_slot != 0 {
+        let expired = find_slot_hash(&ctx.accounts.slot_hashes, collection_state.reveal_slot)?
+            .is_none()
+            && slot > collection_state.reveal_slot;
+        require!(expired, InterchangeableNFTError::RevealPending);
+    }
+
+    collection_state.base_uri = base_uri;
+    collection_state.offset_seed = offset_seed;
+    collection_state.reveal_slot = slot;
+
+    Ok(())
+}
+
+pub fn process_settle_reveal(ctx: Context<SettleReveal>) -> Result<()> {
+    let collection_state = &mut ctx.accounts.collection_state;
+    require!(collection_state.is_pre_reveal(), InterchangeableNFTError::NotPreReveal);
+    require!(
+        collection_state.reveal_slot != 0,
+        InterchangeableNFTError::RevealNotRequested
+    );
+    require!(
+        Clock::get()?.slot > collection_state.reveal_slot,
+        InterchangeableNFTError::CommitmentNotReady
+    );
+    let slot_hash = find_slot_hash(&ctx.accounts.slot_hashes, collection_state.reveal_slot)?
+        .ok_or(InterchangeableNFTError::CommitmentExpired)?;
+
+    let parts: &[&[u8]] = &[
+        &collection_state.offset_seed,
+        &slot_hash,
+        collection_state.collection_mint.as_ref(),
+    ];
+    let reveal_offset = random_u64(parts) % collection_state.max_supply;
+
+    // shuffled collections draw the ids of NFTs minted so far from the
+    // same entropy
+    if collection_state.id_pool != Pubkey::default() {
+        let id_pool = ctx.accounts.id_pool.as_ref().ok_or(InterchangeableNFTError::InvalidIdPool)?;
+        let mut id_pool = id_pool.load_mut()?;
+        id_pool.seed = hashv(parts).to_bytes();
+        id_pool.pre_reveal_supply = collection_state.next_token_id.to_le_bytes();
+    }
+
+    collection_state.reveal_offset = reveal_offset;
+    collection_state.revealed = true;
+
+    emit!(CollectionRevealed {
+        collection_mint: collection_state.collection_mint,
+        base_uri: collection_state.base_uri.clone(),
+        provenance_hash: collection_state.provenance_hash,
+        reveal_offset,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// permissionless crank, remaining_accounts are metadata accounts of
+// collection NFTs minted before the reveal. Shuffled metadata ids are drawn
+// up to the highest token_id sent, so cranking in token_id order spreads
+// the draws over transactions.
+pub fn process_update_revealed_metadata<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateRevealedMetadata<'info>>,
+) -> Result<()> {
+    let collection_state = &ctx.accounts.collection_state;
+    require!(collection_state.revealed, InterchangeableNFTError::NotPreReveal);
+    let mut id_pool = ctx.accounts.id_pool.as_ref().map(|id_pool| id_pool.load_mut()).transpose()?;
+
+    let seeds = &[
+        crate::ID.as_ref(),
+        collection_state.collection_mint.as_ref(),
+        b"pda_creator" as &[u8],
+        &[ctx.bumps.pda_creator],
+    ];
+
+    for nft_metadata in ctx.remaining_accounts.iter() {
+        require!(
+            nft_metadata.owner == &mpl_token_metadata::ID,
+            InterchangeableNFTError::InvalidCollectionNFT
+        );
+        let (token_id, metadata) = verify_collection_nft(
+            nft_metadata,
+            collection_state,
+            ctx.accounts.pda_creator.key,
+        )?;
+
+        // pre-reveal positions are drawn from the reveal seed alone
+        let metadata_id = collection_state.metadata_id(token_id, id_pool.as_deref_mut(), &[])?;
+
+        // keep everything but the URI, including verified creator and collection flags
+        let data = DataV2 {
+            name: metadata.name.trim_end_matches(char::from(0)).to_string(),
+            symbol: metadata.symbol.trim_end_matches(char::from(0)).to_string(),
+            uri: collection_state.token_uri(metadata_id),
+            seller_fee_basis_points: metadata.seller_fee_basis_points,
+            creators: metadata.creators,
+            collection: metadata.collection,
+            uses: metadata.uses,
+        };
+
+        let update_metadata = UpdateMetadataAccountV2 {
+            metadata: nft_metadata.key(),
+            update_authority: ctx.accounts.pda_creator.key(),
+        };
+
+        solana_program::program::invoke_signed(
+            &update_metadata.instruction(UpdateMetadataAccountV2InstructionArgs {
+                data: Some(data),
+                new_update_authority: None,
+                primary_sale_happened: None,
+                is_mutable: None,
+            }),
+            &[
+                nft_metadata.clone(),
+                ctx.accounts.pda_creator.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+    }
+
+    Ok(())
+}
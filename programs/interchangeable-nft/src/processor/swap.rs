@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TransferChecked};
+
+use crate::{
+    constants::{PAUSE_PULL, PAUSE_REDEEM}, error::InterchangeableNFTError, events::*, metadata::{pda_creator_address, verify_collection_nft}, state::*
+};
+
+// Trades an NFT for one held by the vault. Both NFTs have the same backing,
+// so only the swap fee moves and the vault balance is untouched.
+pub fn process_swap(ctx: Context<SwapNft>) -> Result<()> {
+    ctx.accounts.program_state.check_entry_allowed()?;
+    ctx.accounts.collection_state.check_not_paused(PAUSE_REDEEM | PAUSE_PULL)?;
+
+    let collection_state = &ctx.accounts.collection_state;
+
+    // 0. verify both NFTs belong to this collection
+    let pda_creator = pda_creator_address(&collection_state.collection_mint);
+    let (in_token_id, _) = verify_collection_nft(
+        &ctx.accounts.in_nft_metadata.to_account_info(),
+        collection_state,
+        &pda_creator,
+    )?;
+    let (out_token_id, _) = verify_collection_nft(
+        &ctx.accounts.out_nft_metadata.to_account_info(),
+        collection_state,
+        &pda_creator,
+    )?;
+    {
+        let mut vault_inventory = ctx.accounts.vault_inventory.load_mut()?;
+        vault_inventory.remove(out_token_id)?;
+        vault_inventory.insert(in_token_id)?;
+    }
+
+    // 1. transfer incoming NFT to vault
+    let transfer_in_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.payer_in_nft_account.to_account_info(),
+            mint: ctx.accounts.in_nft_mint.to_account_info(),
+            to: ctx.accounts.vault_in_nft_account.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        },
+    );
+    token::transfer_checked(transfer_in_ctx, 1, 0)?;
+
+    // 2. transfer outgoing NFT to payer
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"collection",
+        collection_state.seed_authority.as_ref(),
+        collection_state.collection_mint.as_ref(),
+        &[collection_state.bump],
+    ]];
+    let transfer_out_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_out_nft_account.to_account_info(),
+            mint: ctx.accounts.out_nft_mint.to_account_info(),
+            to: ctx.accounts.payer_out_nft_account.to_account_info(),
+            authority: collection_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer_checked(transfer_out_ctx, 1, 0)?;
+
+    // 3. charge swap fee
+    let swap_fee = ctx.accounts.program_state.effective_swap_fee(collection_state.swap_fee);
+    let fee_amount = collection_state.swap_fee_amount(swap_fee)?;
+    let (collection_fee, protocol_fee) = ctx.accounts.program_state.split_fee(fee_amount)?;
+    if protocol_fee > 0 {
+        let protocol_fee_token_account = ctx.accounts.protocol_fee_token_account
+            .as_ref()
+            .ok_or(InterchangeableNFTError::InvalidFeeReceiver)?;
+        let protocol_fee_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.payer_token_account.to_account_info(),
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                to: protocol_fee_token_account.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            },
+        );
+        token::transfer_checked(
+            protocol_fee_ctx,
+            protocol_fee,
+            ctx.accounts.payment_token_mint.decimals
+        )?;
+    }
+    if collection_fee > 0 {
+        let fee_transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.payer_token_account.to_account_info(),
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                to: ctx.accounts.fee_receiver_token_account.to_account_info(),
+                authority: ctx.accounts.payer.to_account_info(),
+            },
+        );
+        token::transfer_checked(
+            fee_transfer_ctx,
+            collection_fee,
+            ctx.accounts.payment_token_mint.decimals
+        )?;
+    }
+
+    emit!(NFTSwapped {
+        swapper: ctx.accounts.payer.key(),
+        collection_mint: collection_state.collection_mint,
+        in_mint: ctx.accounts.in_nft_mint.key(),
+        in_token_id,
+        out_mint: ctx.accounts.out_nft_mint.key(),
+        out_token_id,
+        fee_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
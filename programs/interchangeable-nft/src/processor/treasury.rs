@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TransferChecked};
+
+use crate::{
+    error::InterchangeableNFTError, events::*, state::*
+};
+
+pub fn process_withdraw_surplus(ctx: Context<WithdrawSurplus>, amount: u64) -> Result<()> {
+    ctx.accounts.program_state.check_not_paused()?;
+    require!(amount > 0, InterchangeableNFTError::InvalidZeroValue);
+
+    let collection_state = &ctx.accounts.admin.collection_state;
+
+    // every outstanding NFT must stay redeemable for the full mint price
+    let required_backing = collection_state.required_backing(collection_state.mint_price)?;
+    let surplus = ctx.accounts.vault_token_account.amount.saturating_sub(required_backing);
+    require!(amount <= surplus, InterchangeableNFTError::InsufficientBacking);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"collection",
+        collection_state.seed_authority.as_ref(),
+        collection_state.collection_mint.as_ref(),
+        &[collection_state.bump],
+    ]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            mint: ctx.accounts.payment_token_mint.to_account_info(),
+            to: ctx.accounts.destination_token_account.to_account_info(),
+            authority: collection_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer_checked(
+        transfer_ctx,
+        amount,
+        ctx.accounts.payment_token_mint.decimals
+    )?;
+    msg!("Surplus withdrawn: {} tokens", amount);
+
+    emit!(TreasuryWithdrawn {
+        authority: ctx.accounts.admin.authority.key(),
+        collection_mint: collection_state.collection_mint,
+        destination: ctx.accounts.destination_token_account.key(),
+        amount,
+        required_backing,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn process_verify_reserves(ctx: Context<VerifyReserves>) -> Result<ReservesReport> {
+    let collection_state = &ctx.accounts.collection_state;
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    let required_backing = collection_state.required_backing(collection_state.mint_price)?;
+
+    let backing_ratio_bps = if required_backing == 0 {
+        u64::MAX
+    } else {
+        (vault_balance as u128 * 10000 / required_backing as u128)
+            .try_into()
+            .unwrap_or(u64::MAX)
+    };
+
+    let report = ReservesReport {
+        vault_balance,
+        required_backing,
+        circulating_supply: collection_state.circulating_supply(),
+        vault_nft_count: collection_state.vault_nft_count,
+        backing_ratio_bps,
+    };
+
+    emit!(ReservesVerified {
+        collection_mint: collection_state.collection_mint,
+        vault_balance,
+        required_backing,
+        circulating_supply: report.circulating_supply,
+        vault_nft_count: report.vault_nft_count,
+        backing_ratio_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    // returned to the caller through set_return_data
+    Ok(report)
+}
@@ -6,8 +6,15 @@ use anchor_spl::{
 
 use crate::{
     state::collection::*, 
+    state::program::*,
+    state::inventory::*,
+    state::registry::*,
+    state::commitment::*,
+    state::phase::*,
+    state::pool::*,
     error::InterchangeableNFTError,
-    constants::FEE_RECEIVER,
+    constants::{GATE_RECORD_SEED, MINT_PHASE_SEED, MINT_RECORD_SEED, PROGRAM_STATE_SEED, PULL_COMMITMENT_SEED, VAULT_INVENTORY_SEED},
+    program::InterchangeableNft,
 };
 
 #[derive(Accounts)]
@@ -24,7 +31,7 @@ pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + std::mem::size_of::<CollectionState>() + 200,
+        space = CollectionState::SPACE,
         seeds = [
             b"collection".as_ref(), 
             authority.key().as_ref(),
@@ -58,10 +65,7 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub collection_authority_record: UncheckedAccount<'info>,
 
-    /// CHECK: Fee receiver account
-    #[account(
-        constraint = fee_receiver.key() == Pubkey::try_from(FEE_RECEIVER).unwrap() @ InterchangeableNFTError::InvalidFeeReceiver
-    )]
+    /// CHECK: Fee receiver account, chosen by the collection authority
     pub fee_receiver: UncheckedAccount<'info>,
 
     /// CHECK: Fee receiver token account
@@ -72,14 +76,29 @@ pub struct Initialize<'info> {
         associated_token::authority = fee_receiver,
     )]
     pub fee_receiver_token_account: Account<'info, TokenAccount>,
+
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VaultInventory::SPACE,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
 }
 
 #[derive(Accounts)]
 pub struct MintNFT<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+
+    /// CHECK: Any wallet, receives the minted NFT
+    pub recipient: UncheckedAccount<'info>,
     
-    #[account(mut, seeds = [b"collection".as_ref(), collection_state.authority.as_ref(), collection_state.collection_mint.as_ref()], bump = collection_state.bump)]
+    #[account(mut, seeds = [b"collection".as_ref(), collection_state.seed_authority.as_ref(), collection_state.collection_mint.as_ref()], bump = collection_state.bump)]
     pub collection_state: Account<'info, CollectionState>,
     
     #[account(mut, constraint = payer_token_account.mint == collection_state.payment_token_mint, constraint = payer_token_account.owner == payer.key())]
@@ -109,7 +128,7 @@ pub struct MintNFT<'info> {
         init_if_needed,
         payer = payer,
         associated_token::mint = nft_mint,
-        associated_token::authority = payer,
+        associated_token::authority = recipient,
     )]
     pub nft_token_account: Account<'info, TokenAccount>,
     
@@ -142,6 +161,298 @@ pub struct MintNFT<'info> {
         seeds::program = crate::ID  
     )]
     pub pda_creator: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+
+    // written when the collection has a token registry
+    #[account(
+        mut,
+        address = collection_state.token_registry @ InterchangeableNFTError::InvalidTokenRegistry
+    )]
+    pub token_registry: Option<AccountLoader<'info, TokenRegistry>>,
+
+    /// CHECK: Optional mint phase config, may not exist
+    #[account(seeds = [MINT_PHASE_SEED, collection_state.key().as_ref()], bump)]
+    pub mint_phase_config: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MintRecord::SPACE,
+        seeds = [MINT_RECORD_SEED, collection_state.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub mint_record: Account<'info, MintRecord>,
+
+    // required once a collection with shuffled metadata ids is revealed
+    #[account(
+        mut,
+        address = collection_state.id_pool @ InterchangeableNFTError::InvalidIdPool
+    )]
+    pub id_pool: Option<AccountLoader<'info, IdPool>>,
+
+    /// CHECK: SlotHashes sysvar, read in place; required with the id pool
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct MintAllowlisted<'info> {
+    pub mint: MintNFT<'info>,
+
+    #[account(
+        seeds = [MINT_PHASE_SEED, mint.collection_state.key().as_ref()],
+        bump = mint_phase_config.bump,
+    )]
+    pub mint_phase_config: Account<'info, MintPhaseConfig>,
+}
+
+#[derive(Accounts)]
+pub struct MintTokenGated<'info> {
+    pub mint: MintNFT<'info>,
+
+    #[account(
+        seeds = [MINT_PHASE_SEED, mint.collection_state.key().as_ref()],
+        bump = mint_phase_config.bump,
+    )]
+    pub mint_phase_config: Account<'info, MintPhaseConfig>,
+
+    // a fungible token with collection metadata must not pass as a gate NFT
+    #[account(
+        constraint = gate_nft_mint.decimals == 0 @ InterchangeableNFTError::InvalidGateNFT,
+        constraint = gate_nft_mint.supply == 1 @ InterchangeableNFTError::InvalidGateNFT,
+    )]
+    pub gate_nft_mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = gate_nft_account.mint == gate_nft_mint.key() @ InterchangeableNFTError::InvalidGateNFT,
+        constraint = gate_nft_account.owner == mint.recipient.key() @ InterchangeableNFTError::InvalidGateNFT,
+        constraint = gate_nft_account.amount == 1 @ InterchangeableNFTError::InvalidGateNFT,
+    )]
+    pub gate_nft_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is the gate nft metadata account
+    #[account(
+        seeds = [
+            b"metadata",
+            mpl_token_metadata::ID.as_ref(),
+            gate_nft_mint.key().as_ref()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub gate_nft_metadata: UncheckedAccount<'info>,
+
+    // required in PHASE_GATE_TOKEN_ONCE phases
+    #[account(
+        init_if_needed,
+        payer = mint.payer,
+        space = GateRecord::SPACE,
+        seeds = [GATE_RECORD_SEED, mint.collection_state.key().as_ref(), gate_nft_mint.key().as_ref()],
+        bump
+    )]
+    pub gate_record: Option<Account<'info, GateRecord>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintPhase<'info> {
+    pub admin: AdminOnly<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin.authority,
+        space = MintPhaseConfig::SPACE,
+        seeds = [MINT_PHASE_SEED, admin.collection_state.key().as_ref()],
+        bump
+    )]
+    pub mint_phase_config: Account<'info, MintPhaseConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintPhases<'info> {
+    pub admin: AdminOnly<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin.authority,
+        space = MintPhaseConfig::SPACE,
+        seeds = [MINT_PHASE_SEED, admin.collection_state.key().as_ref()],
+        bump
+    )]
+    pub mint_phase_config: Account<'info, MintPhaseConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any wallet, receives the minted NFT
+    pub recipient: UncheckedAccount<'info>,
+    
+    #[account(mut, seeds = [b"collection".as_ref(), collection_state.seed_authority.as_ref(), collection_state.collection_mint.as_ref()], bump = collection_state.bump)]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    #[account(mut, constraint = payer_token_account.mint == collection_state.payment_token_mint, constraint = payer_token_account.owner == payer.key())]
+    pub payer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, constraint = vault_token_account.mint == collection_state.payment_token_mint, constraint = vault_token_account.owner == collection_state.key())]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// CHECK: Validated in instruction
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(address = collection_state.collection_mint)]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(mut)]
+    pub collection_authority_record: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(
+        mut,
+        seeds = [
+            crate::ID.as_ref(),
+            collection_mint.key().as_ref(),
+            b"pda_creator",
+        ],
+        bump,
+        seeds::program = crate::ID  
+    )]
+    pub pda_creator: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+
+    // written when the collection has a token registry
+    #[account(
+        mut,
+        address = collection_state.token_registry @ InterchangeableNFTError::InvalidTokenRegistry
+    )]
+    pub token_registry: Option<AccountLoader<'info, TokenRegistry>>,
+
+    /// CHECK: Optional mint phase config, may not exist
+    #[account(seeds = [MINT_PHASE_SEED, collection_state.key().as_ref()], bump)]
+    pub mint_phase_config: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MintRecord::SPACE,
+        seeds = [MINT_RECORD_SEED, collection_state.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub mint_record: Account<'info, MintRecord>,
+
+    // required once a collection with shuffled metadata ids is revealed
+    #[account(
+        mut,
+        address = collection_state.id_pool @ InterchangeableNFTError::InvalidIdPool
+    )]
+    pub id_pool: Option<AccountLoader<'info, IdPool>>,
+
+    /// CHECK: SlotHashes sysvar, read in place; required with the id pool
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct AuthorityMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Any wallet, receives the minted NFT
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"collection".as_ref(), collection_state.seed_authority.as_ref(), collection_state.collection_mint.as_ref()],
+        bump = collection_state.bump,
+        has_one = authority @ InterchangeableNFTError::OnlyOwner
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+
+    #[account(mut, constraint = authority_token_account.mint == collection_state.payment_token_mint, constraint = authority_token_account.owner == authority.key())]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = vault_token_account.mint == collection_state.payment_token_mint, constraint = vault_token_account.owner == collection_state.key())]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: Validated in instruction
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(address = collection_state.collection_mint)]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(mut)]
+    pub collection_authority_record: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in instruction
+    #[account(
+        mut,
+        seeds = [
+            crate::ID.as_ref(),
+            collection_mint.key().as_ref(),
+            b"pda_creator",
+        ],
+        bump,
+        seeds::program = crate::ID
+    )]
+    pub pda_creator: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+
+    // written when the collection has a token registry
+    #[account(
+        mut,
+        address = collection_state.token_registry @ InterchangeableNFTError::InvalidTokenRegistry
+    )]
+    pub token_registry: Option<AccountLoader<'info, TokenRegistry>>,
+
+    // required once a collection with shuffled metadata ids is revealed
+    #[account(
+        mut,
+        address = collection_state.id_pool @ InterchangeableNFTError::InvalidIdPool
+    )]
+    pub id_pool: Option<AccountLoader<'info, IdPool>>,
+
+    /// CHECK: SlotHashes sysvar, read in place; required with the id pool
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
@@ -153,7 +464,7 @@ pub struct RedeemNFT<'info> {
         mut,
         seeds = [
             b"collection".as_ref(), 
-            collection_state.authority.as_ref(),
+            collection_state.seed_authority.as_ref(),
             collection_state.collection_mint.as_ref() 
         ],
         bump = collection_state.bump,
@@ -195,7 +506,7 @@ pub struct RedeemNFT<'info> {
     /// CHECK: This is the fee receiver account
     #[account(
         mut,
-        constraint = fee_receiver.key() == Pubkey::try_from(FEE_RECEIVER).unwrap() @ InterchangeableNFTError::InvalidFeeReceiver
+        constraint = fee_receiver.key() == collection_state.fee_receiver @ InterchangeableNFTError::InvalidFeeReceiver
     )]
     pub fee_receiver: UncheckedAccount<'info>,
 
@@ -221,10 +532,40 @@ pub struct RedeemNFT<'info> {
         bump
     )]
     pub nft_metadata: UncheckedAccount<'info>,
+
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+
+    // receives the protocol's share of the fee, required while
+    // program_state.protocol_fee_bps > 0
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.mint == collection_state.payment_token_mint,
+        constraint = protocol_fee_token_account.owner == program_state.fee_receiver @ InterchangeableNFTError::InvalidFeeReceiver,
+    )]
+    pub protocol_fee_token_account: Option<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
-pub struct PullNft<'info> {
+pub struct RedeemTo<'info> {
+    pub redeem: RedeemNFT<'info>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == redeem.collection_state.payment_token_mint @ InterchangeableNFTError::InvalidPaymentToken,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemBatch<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     
@@ -232,47 +573,212 @@ pub struct PullNft<'info> {
         mut,
         seeds = [
             b"collection".as_ref(), 
-            collection_state.authority.as_ref(),
-            collection_state.collection_mint.as_ref()
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref() 
         ],
         bump = collection_state.bump,
     )]
     pub collection_state: Account<'info, CollectionState>,
     
-    // NFT 
-    pub nft_mint: Account<'info, Mint>,
+    #[account(address = collection_state.payment_token_mint @ InterchangeableNFTError::InvalidPaymentToken)]
+    pub payment_token_mint: Account<'info, Mint>,
     
     #[account(
         mut,
-        associated_token::mint = nft_mint,
-        associated_token::authority = payer,
+        constraint = payer_token_account.mint == collection_state.payment_token_mint,
+        constraint = payer_token_account.owner == payer.key(),
     )]
-    pub payer_nft_account: Account<'info, TokenAccount>,
+    pub payer_token_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        associated_token::mint = nft_mint,
-        associated_token::authority = collection_state,
+        constraint = vault_token_account.mint == collection_state.payment_token_mint,
+        constraint = vault_token_account.owner == collection_state.key(),
     )]
-    pub vault_nft_account: Account<'info, TokenAccount>,
-    
-    // payer account
+    pub vault_token_account: Account<'info, TokenAccount>,
+
     #[account(
         mut,
-        associated_token::mint = collection_state.payment_token_mint,
-        associated_token::authority = payer,
+        constraint = fee_receiver_token_account.mint == collection_state.payment_token_mint,
+        constraint = fee_receiver_token_account.owner == collection_state.fee_receiver @ InterchangeableNFTError::InvalidFeeReceiver,
     )]
-    pub payer_token_account: Account<'info, TokenAccount>,
+    pub fee_receiver_token_account: Account<'info, TokenAccount>,
     
+    pub token_program: Program<'info, Token>,
+
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+
     #[account(
         mut,
-        associated_token::mint = collection_state.payment_token_mint,
-        associated_token::authority = collection_state,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    
-    pub token_program: Program<'info, Token>,
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+
+    // receives the protocol's share of the fee, required while
+    // program_state.protocol_fee_bps > 0
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.mint == collection_state.payment_token_mint,
+        constraint = protocol_fee_token_account.owner == program_state.fee_receiver @ InterchangeableNFTError::InvalidFeeReceiver,
+    )]
+    pub protocol_fee_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct PullNft<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    // NFT 
+    pub nft_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_nft_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_nft_account: Account<'info, TokenAccount>,
+    
+    // payer account
+    #[account(
+        mut,
+        associated_token::mint = collection_state.payment_token_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = collection_state.payment_token_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+
+    /// CHECK: This is nft metadata account
+    #[account(
+        seeds = [
+            b"metadata",
+            mpl_token_metadata::ID.as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+}
+
+#[derive(Accounts)]
+pub struct PullNftById<'info> {
+    pub pull: PullNft<'info>,
+    
+    #[account(address = pull.collection_state.token_registry @ InterchangeableNFTError::InvalidTokenRegistry)]
+    pub token_registry: AccountLoader<'info, TokenRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCollectionConfig<'info> {
+    pub admin: AdminOnly<'info>,
+    
+    #[account(
+        associated_token::mint = admin.collection_state.payment_token_mint,
+        associated_token::authority = admin.collection_state,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    pub admin: AdminOnly<'info>,
+    
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+    
+    /// CHECK: New fee receiver, only its token account is used
+    pub fee_receiver: UncheckedAccount<'info>,
+    
+    #[account(
+        constraint = fee_receiver_token_account.mint == admin.collection_state.payment_token_mint,
+        constraint = fee_receiver_token_account.owner == fee_receiver.key(),
+    )]
+    pub fee_receiver_token_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSurplus<'info> {
+    pub admin: AdminOnly<'info>,
+    
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(address = admin.collection_state.payment_token_mint @ InterchangeableNFTError::InvalidPaymentToken)]
+    pub payment_token_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = admin.collection_state,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == payment_token_mint.key() @ InterchangeableNFTError::InvalidPaymentToken,
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyReserves<'info> {
+    #[account(
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    #[account(
+        associated_token::mint = collection_state.payment_token_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
 }
 
 #[derive(Accounts)]
@@ -284,20 +790,568 @@ pub struct AdminOnly<'info> {
         mut,
         seeds = [
             b"collection".as_ref(), 
-            collection_state.authority.as_ref(),
+            collection_state.seed_authority.as_ref(),
             collection_state.collection_mint.as_ref()  
         ],
         bump = collection_state.bump,
-        constraint = collection_state.authority == authority.key() @ InterchangeableNFTError::OnlyOwner
+        has_one = authority @ InterchangeableNFTError::OnlyOwner
     )]
     pub collection_state: Account<'info, CollectionState>,
 }
 
 #[derive(Accounts)]
-pub struct CollectionAuthority<'info> {
-    #[account(mut)]
+pub struct Reveal<'info> {
+    pub admin: AdminOnly<'info>,
+
+    /// CHECK: SlotHashes sysvar, read in place
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleReveal<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(),
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+
+    /// CHECK: SlotHashes sysvar, read in place
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    // receives the reveal entropy when the collection shuffles metadata ids
+    #[account(
+        mut,
+        address = collection_state.id_pool @ InterchangeableNFTError::InvalidIdPool
+    )]
+    pub id_pool: Option<AccountLoader<'info, IdPool>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRevealedMetadata<'info> {
+    #[account(
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
     pub collection_state: Account<'info, CollectionState>,
+
+    /// CHECK: Update authority of the collection NFTs
+    #[account(
+        seeds = [
+            crate::ID.as_ref(),
+            collection_state.collection_mint.as_ref(),
+            b"pda_creator",
+        ],
+        bump,
+    )]
+    pub pda_creator: UncheckedAccount<'info>,
+
+    /// CHECK: Metaplex token metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    // required when the collection shuffles metadata ids
+    #[account(
+        mut,
+        address = collection_state.id_pool @ InterchangeableNFTError::InvalidIdPool
+    )]
+    pub id_pool: Option<AccountLoader<'info, IdPool>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
     
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()  
+        ],
+        bump = collection_state.bump,
+        constraint = collection_state.pending_authority == Some(pending_authority.key()) @ InterchangeableNFTError::InvalidPendingAuthority
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateCollectionState<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
-}
\ No newline at end of file
+    
+    /// CHECK: too small to deserialize until it is grown, the layout, authority
+    /// and seeds are checked in the instruction
+    #[account(mut, owner = crate::ID)]
+    pub collection_state: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgramState<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = admin,
+        space = ProgramState::SPACE,
+        seeds = [PROGRAM_STATE_SEED],
+        bump
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, InterchangeableNft>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ InterchangeableNFTError::InvalidUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProtocolAdmin<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROGRAM_STATE_SEED],
+        bump = program_state.bump,
+        has_one = admin @ InterchangeableNFTError::OnlyOwner,
+        realloc = ProgramState::SPACE,
+        realloc::payer = admin,
+        realloc::zero = false,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultInventory<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = VaultInventory::SPACE,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncVaultInventory<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    pub nft_mint: Account<'info, Mint>,
+    
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = collection_state,
+        constraint = vault_nft_account.amount == 1 @ InterchangeableNFTError::VaultInventoryMismatch,
+    )]
+    pub vault_nft_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is nft metadata account
+    #[account(
+        seeds = [
+            b"metadata",
+            mpl_token_metadata::ID.as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTokenRegistry<'info> {
+    pub admin: AdminOnly<'info>,
+    
+    #[account(zero)]
+    pub token_registry: AccountLoader<'info, TokenRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeIdPool<'info> {
+    pub admin: AdminOnly<'info>,
+    
+    #[account(zero)]
+    pub id_pool: AccountLoader<'info, IdPool>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterNft<'info> {
+    #[account(
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    pub nft_mint: Account<'info, Mint>,
+    
+    /// CHECK: This is nft metadata account
+    #[account(
+        seeds = [
+            b"metadata",
+            mpl_token_metadata::ID.as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = collection_state.token_registry @ InterchangeableNFTError::InvalidTokenRegistry
+    )]
+    pub token_registry: AccountLoader<'info, TokenRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct CommitPullRandom<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = PullCommitment::SPACE,
+        seeds = [PULL_COMMITMENT_SEED, collection_state.key().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub commitment: Account<'info, PullCommitment>,
+    
+    #[account(
+        mut,
+        associated_token::mint = collection_state.payment_token_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = collection_state.payment_token_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+    
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealPullRandom<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [PULL_COMMITMENT_SEED, collection_state.key().as_ref(), payer.key().as_ref()],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, PullCommitment>,
+    
+    /// CHECK: SlotHashes sysvar, read in place
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+    
+    #[account(address = collection_state.token_registry @ InterchangeableNFTError::InvalidTokenRegistry)]
+    pub token_registry: AccountLoader<'info, TokenRegistry>,
+    
+    pub nft_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_nft_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_nft_account: Account<'info, TokenAccount>,
+    
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPullRandom<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [PULL_COMMITMENT_SEED, collection_state.key().as_ref(), payer.key().as_ref()],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, PullCommitment>,
+    
+    /// CHECK: SlotHashes sysvar, read in place
+    #[account(address = solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+    
+    #[account(address = collection_state.payment_token_mint @ InterchangeableNFTError::InvalidPaymentToken)]
+    pub payment_token_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = fee_receiver_token_account.mint == collection_state.payment_token_mint,
+        constraint = fee_receiver_token_account.owner == collection_state.fee_receiver @ InterchangeableNFTError::InvalidFeeReceiver,
+    )]
+    pub fee_receiver_token_account: Account<'info, TokenAccount>,
+    
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SwapNft<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"collection".as_ref(), 
+            collection_state.seed_authority.as_ref(),
+            collection_state.collection_mint.as_ref()
+        ],
+        bump = collection_state.bump,
+    )]
+    pub collection_state: Account<'info, CollectionState>,
+    
+    // NFT moving into the vault
+    pub in_nft_mint: Account<'info, Mint>,
+
+    /// CHECK: This is nft metadata account
+    #[account(
+        seeds = [
+            b"metadata",
+            mpl_token_metadata::ID.as_ref(),
+            in_nft_mint.key().as_ref()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub in_nft_metadata: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = payer_in_nft_account.mint == in_nft_mint.key(),
+        constraint = payer_in_nft_account.owner == payer.key(),
+    )]
+    pub payer_in_nft_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = in_nft_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_in_nft_account: Account<'info, TokenAccount>,
+    
+    // NFT leaving the vault
+    pub out_nft_mint: Account<'info, Mint>,
+
+    /// CHECK: This is nft metadata account
+    #[account(
+        seeds = [
+            b"metadata",
+            mpl_token_metadata::ID.as_ref(),
+            out_nft_mint.key().as_ref()
+        ],
+        seeds::program = mpl_token_metadata::ID,
+        bump
+    )]
+    pub out_nft_metadata: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = out_nft_mint,
+        associated_token::authority = collection_state,
+    )]
+    pub vault_out_nft_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = out_nft_mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_out_nft_account: Account<'info, TokenAccount>,
+    
+    // swap fee
+    #[account(address = collection_state.payment_token_mint @ InterchangeableNFTError::InvalidPaymentToken)]
+    pub payment_token_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == collection_state.payment_token_mint,
+        constraint = payer_token_account.owner == payer.key(),
+    )]
+    pub payer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = fee_receiver_token_account.mint == collection_state.payment_token_mint,
+        constraint = fee_receiver_token_account.owner == collection_state.fee_receiver @ InterchangeableNFTError::InvalidFeeReceiver,
+    )]
+    pub fee_receiver_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_INVENTORY_SEED, collection_state.key().as_ref()],
+        bump = vault_inventory.load()?.bump,
+    )]
+    pub vault_inventory: AccountLoader<'info, VaultInventory>,
+    
+    #[account(seeds = [PROGRAM_STATE_SEED], bump = program_state.bump)]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    // receives the protocol's share of the fee, required while
+    // program_state.protocol_fee_bps > 0
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.mint == collection_state.payment_token_mint,
+        constraint = protocol_fee_token_account.owner == program_state.fee_receiver @ InterchangeableNFTError::InvalidFeeReceiver,
+    )]
+    pub protocol_fee_token_account: Option<Account<'info, TokenAccount>>,
+}
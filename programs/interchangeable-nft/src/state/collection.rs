@@ -1,8 +1,61 @@
 use anchor_lang::prelude::*;
+use crate::constants::{MAX_URI_LENGTH, PAUSE_ALL};
 use crate::error::*;
+use crate::state::IdPool;
 
 #[account]
 pub struct CollectionState {
+    pub authority: Pubkey,
+    pub collection_mint: Pubkey,
+    pub payment_token_mint: Pubkey,
+    pub mint_price: u64,
+    pub max_supply: u64,
+    pub next_token_id: u64,
+    pub base_uri: String,
+    pub pause_flags: u8,
+    pub bump: u8,
+    pub name: String,
+    pub symbol: String,
+    pub fee_receiver: Pubkey,
+    pub royalty_config: RoyaltyConfig,
+    pub redeem_fee: u16,
+    pub token_decimals: u8,
+    // authority key used in the PDA seeds, fixed at initialize so the
+    // collection address survives authority transfers
+    pub seed_authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    // NFTs redeemed into the vault and not yet pulled out
+    pub vault_nft_count: u64,
+    pub token_registry: Pubkey,
+    // random pulls paid for and not yet revealed
+    pub pending_random_pulls: u64,
+    // charged on swap_nft, in basis points of mint_price
+    pub swap_fee: u16,
+    // mints per receiving wallet across all phases, None means unlimited
+    pub max_mints_per_wallet: Option<u32>,
+    // part of max_supply only the authority can mint, see authority_mint
+    pub reserved_supply: u64,
+    pub reserved_minted: u64,
+    // delayed reveal: NFTs are minted with placeholder_uri until reveal
+    pub placeholder_uri: String,
+    pub provenance_hash: [u8; 32],
+    pub revealed: bool,
+    pub reveal_offset: u64,
+    // shuffled token id assignment when set, see IdPool
+    pub id_pool: Pubkey,
+    // hash of the reveal offset seed, committed at initialize
+    pub offset_seed_hash: [u8; 32],
+    // seed and slot recorded by reveal, the offset is drawn by settle_reveal
+    // from the seed and the hash of reveal_slot, 0 while no reveal is pending
+    pub offset_seed: [u8; 32],
+    pub reveal_slot: u64,
+}
+
+// CollectionState as first deployed, before seed_authority and the fields
+// after it existed; the current layout starts with the same bytes except
+// that pause_flags was a paused bool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyCollectionState {
     pub authority: Pubkey,
     pub collection_mint: Pubkey,
     pub payment_token_mint: Pubkey,
@@ -20,6 +73,65 @@ pub struct CollectionState {
     pub token_decimals: u8,
 }
 
+impl LegacyCollectionState {
+    // legacy accounts are derived from the authority, which could not be transferred
+    pub fn migrate(self) -> CollectionState {
+        CollectionState {
+            authority: self.authority,
+            collection_mint: self.collection_mint,
+            payment_token_mint: self.payment_token_mint,
+            mint_price: self.mint_price,
+            max_supply: self.max_supply,
+            next_token_id: self.next_token_id,
+            base_uri: self.base_uri,
+            pause_flags: if self.paused { PAUSE_ALL } else { 0 },
+            bump: self.bump,
+            name: self.name,
+            symbol: self.symbol,
+            fee_receiver: self.fee_receiver,
+            royalty_config: self.royalty_config,
+            redeem_fee: self.redeem_fee,
+            token_decimals: self.token_decimals,
+            seed_authority: self.authority,
+            pending_authority: None,
+            vault_nft_count: 0,
+            token_registry: Pubkey::default(),
+            pending_random_pulls: 0,
+            swap_fee: 0,
+            max_mints_per_wallet: None,
+            reserved_supply: 0,
+            reserved_minted: 0,
+            placeholder_uri: String::new(),
+            provenance_hash: [0; 32],
+            revealed: false,
+            reveal_offset: 0,
+            id_pool: Pubkey::default(),
+            offset_seed_hash: [0; 32],
+            offset_seed: [0; 32],
+            reveal_slot: 0,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PreRevealConfig {
+    pub placeholder_uri: String,
+    // hash of the ordered final metadata, published before mint
+    pub provenance_hash: [u8; 32],
+    // hash of the seed later passed to reveal
+    pub offset_seed_hash: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReservesReport {
+    pub vault_balance: u64,
+    pub required_backing: u64,
+    pub circulating_supply: u64,
+    pub vault_nft_count: u64,
+    // vault balance over required backing, u64::MAX when nothing circulates
+    pub backing_ratio_bps: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct RoyaltyConfig {
     pub basis_points: u16,
@@ -27,6 +139,8 @@ pub struct RoyaltyConfig {
 }
 
 impl CollectionState {
+    pub const SPACE: usize = 8 + std::mem::size_of::<CollectionState>() + 200 + MAX_URI_LENGTH;
+
     pub fn validate_authority(&self, authority: &Pubkey) -> Result<()> {
         require!(
             self.authority == *authority,
@@ -35,17 +149,111 @@ impl CollectionState {
         Ok(())
     }
     
-    pub fn check_not_paused(&self) -> Result<()> {
-        require!(!self.paused, InterchangeableNFTError::ProgramPaused);
+    pub fn check_not_paused(&self, flag: u8) -> Result<()> {
+        require!(self.pause_flags & flag == 0, InterchangeableNFTError::ProgramPaused);
         Ok(())
     }
 
-    pub fn validate_mint_price(&self, payment_amount: u64) -> Result<()> {
+    pub fn is_pre_reveal(&self) -> bool {
+        !self.placeholder_uri.is_empty() && !self.revealed
+    }
+
+    // metadata id of `token_id`, None before the reveal. Token ids are
+    // shifted by the random reveal offset, 0 without delayed reveal, or
+    // mapped through the id pool when the collection shuffles ids
+    pub fn metadata_id(
+        &self,
+        token_id: u64,
+        id_pool: Option<&mut IdPool>,
+        entropy: &[u8],
+    ) -> Result<Option<u64>> {
+        if self.is_pre_reveal() {
+            return Ok(None);
+        }
+        let metadata_id = if self.id_pool == Pubkey::default() {
+            (token_id + self.reveal_offset) % self.max_supply
+        } else {
+            id_pool
+                .ok_or(InterchangeableNFTError::InvalidIdPool)?
+                .metadata_id(token_id, self.max_supply, entropy)?
+        };
+        Ok(Some(metadata_id))
+    }
+
+    pub fn token_uri(&self, metadata_id: Option<u64>) -> String {
+        match metadata_id {
+            Some(metadata_id) => format!("{}/{}", self.base_uri, metadata_id),
+            None => self.placeholder_uri.clone(),
+        }
+    }
+
+    pub fn reserved_remaining(&self) -> u64 {
+        self.reserved_supply.saturating_sub(self.reserved_minted)
+    }
+
+    // public mints must leave room for the unminted reserve
+    pub fn check_public_supply(&self, count: u64) -> Result<()> {
+        let required = self.next_token_id
+            .checked_add(count)
+            .and_then(|supply| supply.checked_add(self.reserved_remaining()))
+            .ok_or(InterchangeableNFTError::MathOverflow)?;
+        require!(required <= self.max_supply, InterchangeableNFTError::NoAvailableNFTs);
+        Ok(())
+    }
+
+    // the amount charged must not exceed what the user signed for
+    pub fn validate_max_payment(&self, amount: u64, max_payment: u64) -> Result<()> {
         require!(
-            payment_amount >= self.mint_price,
-            InterchangeableNFTError::InvalidMintPrice
+            amount <= max_payment,
+            InterchangeableNFTError::PriceExceedsMaxPayment
         );
         Ok(())
     }
 
-} 
\ No newline at end of file
+    // NFTs held outside the vault, each one redeemable; NFTs reserved
+    // by a pending random pull are already paid for
+    pub fn circulating_supply(&self) -> u64 {
+        self.next_token_id
+            .saturating_sub(self.vault_nft_count)
+            .saturating_add(self.pending_random_pulls)
+    }
+
+    // vault NFTs not reserved by a pending random pull
+    pub fn check_vault_available(&self) -> Result<()> {
+        require!(
+            self.vault_nft_count > self.pending_random_pulls,
+            InterchangeableNFTError::NoAvailableNFTs
+        );
+        Ok(())
+    }
+
+    // `swap_fee` is the collection's fee after ProgramState::effective_swap_fee
+    pub fn swap_fee_amount(&self, swap_fee: u16) -> Result<u64> {
+        self.mint_price
+            .checked_mul(swap_fee as u64)
+            .ok_or(InterchangeableNFTError::MathOverflow)?
+            .checked_div(10000)
+            .ok_or_else(|| error!(InterchangeableNFTError::MathOverflow))
+    }
+
+    // split a redemption of `mint_price` into (fee_amount, user_amount)
+    pub fn redeem_amounts(&self, redeem_fee: u16) -> Result<(u64, u64)> {
+        let fee_amount = self.mint_price
+            .checked_mul(redeem_fee as u64)
+            .ok_or(InterchangeableNFTError::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(InterchangeableNFTError::MathOverflow)?;
+        let user_amount = self.mint_price
+            .checked_sub(fee_amount)
+            .ok_or(InterchangeableNFTError::MathOverflow)?;
+        Ok((fee_amount, user_amount))
+    }
+
+    // tokens the vault must hold so every circulating NFT can be redeemed at `mint_price`
+    pub fn required_backing(&self, mint_price: u64) -> Result<u64> {
+        self.circulating_supply()
+            .checked_mul(mint_price)
+            .ok_or_else(|| error!(InterchangeableNFTError::MathOverflow))
+    }
+
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+// a paid random pull waiting for its reveal, one per (collection, payer)
+#[account]
+pub struct PullCommitment {
+    pub collection_state: Pubkey,
+    pub payer: Pubkey,
+    pub seed_hash: [u8; 32],
+    pub commit_slot: u64,
+    pub amount: u64,
+    pub bump: u8,
+    // the draw is over the vault as it was at commit
+    pub inventory_generation: u64,
+}
+
+impl PullCommitment {
+    pub const SPACE: usize = 8 + std::mem::size_of::<PullCommitment>();
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::*};
+
+// one bit per token_id, set while the NFT sits in the collection vault
+#[account(zero_copy)]
+pub struct VaultInventory {
+    pub collection_state: Pubkey,
+    pub bump: u8,
+    pub bitmap: [u8; VAULT_INVENTORY_BYTES],
+    // little-endian u64 bumped on every insert and remove
+    pub generation: [u8; 8],
+}
+
+impl VaultInventory {
+    pub const SPACE: usize = 8 + std::mem::size_of::<VaultInventory>();
+
+    fn position(token_id: u64) -> Result<(usize, u8)> {
+        require!(
+            token_id < MAX_MAX_SUPPLY,
+            InterchangeableNFTError::ExceedMaxValue
+        );
+        Ok(((token_id / 8) as usize, 1 << (token_id % 8)))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.bitmap.iter().map(|byte| byte.count_ones() as u64).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bitmap.iter().all(|byte| *byte == 0)
+    }
+
+    // token_id of the n-th NFT in the vault, in token_id order
+    pub fn nth(&self, mut n: u64) -> Option<u64> {
+        for (index, byte) in self.bitmap.iter().enumerate() {
+            let ones = byte.count_ones() as u64;
+            if n < ones {
+                let mut bits = *byte;
+                for _ in 0..n {
+                    bits &= bits - 1;
+                }
+                return Some(index as u64 * 8 + bits.trailing_zeros() as u64);
+            }
+            n -= ones;
+        }
+        None
+    }
+
+    pub fn generation(&self) -> u64 {
+        u64::from_le_bytes(self.generation)
+    }
+
+    fn bump_generation(&mut self) {
+        self.generation = self.generation().wrapping_add(1).to_le_bytes();
+    }
+
+    pub fn contains(&self, token_id: u64) -> Result<bool> {
+        let (index, mask) = Self::position(token_id)?;
+        Ok(self.bitmap[index] & mask != 0)
+    }
+
+    pub fn insert(&mut self, token_id: u64) -> Result<()> {
+        let (index, mask) = Self::position(token_id)?;
+        require!(
+            self.bitmap[index] & mask == 0,
+            InterchangeableNFTError::VaultInventoryMismatch
+        );
+        self.bitmap[index] |= mask;
+        self.bump_generation();
+        Ok(())
+    }
+
+    pub fn remove(&mut self, token_id: u64) -> Result<()> {
+        let (index, mask) = Self::position(token_id)?;
+        require!(
+            self.bitmap[index] & mask != 0,
+            InterchangeableNFTError::VaultInventoryMismatch
+        );
+        self.bitmap[index] &= !mask;
+        self.bump_generation();
+        Ok(())
+    }
+}
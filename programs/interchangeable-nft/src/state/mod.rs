@@ -1,5 +1,17 @@
 pub mod collection;
 pub mod account;
+pub mod program;
+pub mod inventory;
+pub mod registry;
+pub mod commitment;
+pub mod phase;
+pub mod pool;
 
 pub use collection::*;
-pub use account::*; 
\ No newline at end of file
+pub use account::*;
+pub use program::*;
+pub use inventory::*;
+pub use registry::*;
+pub use commitment::*;
+pub use phase::*;
+pub use pool::*; 
\ No newline at end of file
@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        MAX_MINT_PHASES, PHASE_GATE_ALLOWLIST, PHASE_GATE_OPEN, PHASE_GATE_TOKEN, PHASE_GATE_TOKEN_ONCE,
+    },
+    error::InterchangeableNFTError,
+};
+
+// one time window of the mint schedule
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MintPhase {
+    pub start_ts: i64,
+    pub end_ts: i64,
+    // 0 means the collection mint_price
+    pub price: u64,
+    // mints per wallet in this phase, 0 means unlimited
+    pub wallet_cap: u32,
+    // PHASE_GATE_OPEN, PHASE_GATE_ALLOWLIST, PHASE_GATE_TOKEN or
+    // PHASE_GATE_TOKEN_ONCE (each gate NFT can be used only once)
+    pub gate: u8,
+    // allowlist phases only
+    pub merkle_root: [u8; 32],
+    // token-gated phases only
+    pub gate_collection: Pubkey,
+}
+
+impl MintPhase {
+    pub const SIZE: usize = 8 + 8 + 8 + 4 + 1 + 32 + 32;
+
+    pub fn is_active(&self, now: i64) -> bool {
+        self.start_ts <= now && now < self.end_ts
+    }
+
+    pub fn is_token_gated(&self) -> bool {
+        self.gate == PHASE_GATE_TOKEN || self.gate == PHASE_GATE_TOKEN_ONCE
+    }
+
+    fn accepts(&self, gate: u8) -> bool {
+        match gate {
+            PHASE_GATE_TOKEN => self.is_token_gated(),
+            _ => self.gate == gate,
+        }
+    }
+
+    // every NFT must stay backed by mint_price in the vault
+    pub fn effective_price(&self, mint_price: u64) -> Result<u64> {
+        if self.price == 0 {
+            return Ok(mint_price);
+        }
+        require!(self.price >= mint_price, InterchangeableNFTError::InvalidPhasePrice);
+        Ok(self.price)
+    }
+}
+
+// mint schedule of a collection; without this account minting is public
+#[account]
+pub struct MintPhaseConfig {
+    pub collection_state: Pubkey,
+    // root over leaves hash(wallet, quota, price), see utils::merkle,
+    // used by mint_allowlisted when no phases are scheduled
+    pub merkle_root: [u8; 32],
+    // close mint_nft / mint_batch while the allowlist phase runs
+    pub allowlist_only: bool,
+    pub bump: u8,
+    // ordered, non-overlapping; when set they replace the fields above
+    pub phases: Vec<MintPhase>,
+}
+
+impl MintPhaseConfig {
+    pub const SPACE: usize = 8 + std::mem::size_of::<MintPhaseConfig>() + 64
+        + MAX_MINT_PHASES * MintPhase::SIZE;
+
+    // mint_phase_config is passed unchecked to the public mint, it may not exist
+    pub fn load_optional(mint_phase_config: &AccountInfo) -> Result<Option<MintPhaseConfig>> {
+        if mint_phase_config.data_is_empty() {
+            return Ok(None);
+        }
+        let config = MintPhaseConfig::try_deserialize(&mut &mint_phase_config.try_borrow_data()?[..])?;
+        Ok(Some(config))
+    }
+
+    pub fn validate_phases(phases: &[MintPhase]) -> Result<()> {
+        require!(phases.len() <= MAX_MINT_PHASES, InterchangeableNFTError::InvalidMintPhases);
+        for (i, phase) in phases.iter().enumerate() {
+            require!(phase.start_ts < phase.end_ts, InterchangeableNFTError::InvalidMintPhases);
+            require!(phase.gate <= PHASE_GATE_TOKEN_ONCE, InterchangeableNFTError::InvalidMintPhases);
+            if phase.is_token_gated() {
+                require!(
+                    phase.gate_collection != Pubkey::default(),
+                    InterchangeableNFTError::InvalidMintPhases
+                );
+            }
+            if i > 0 {
+                require!(
+                    phases[i - 1].end_ts <= phase.start_ts,
+                    InterchangeableNFTError::InvalidMintPhases
+                );
+            }
+        }
+        Ok(())
+    }
+
+    // phase index and phase for `gate` at `now`; None when no phases are scheduled
+    pub fn current_phase(&self, now: i64, gate: u8) -> Result<Option<(usize, MintPhase)>> {
+        if self.phases.is_empty() {
+            if gate == PHASE_GATE_OPEN {
+                require!(!self.allowlist_only, InterchangeableNFTError::PublicMintClosed);
+            }
+            return Ok(None);
+        }
+        let (index, phase) = self.phases.iter()
+            .enumerate()
+            .find(|(_, phase)| phase.is_active(now))
+            .ok_or(InterchangeableNFTError::MintPhaseClosed)?;
+        require!(phase.accepts(gate), InterchangeableNFTError::MintPhaseGated);
+        Ok(Some((index, *phase)))
+    }
+
+    pub fn allowlist_root(&self, phase: Option<&MintPhase>) -> [u8; 32] {
+        match phase {
+            Some(phase) if phase.gate == PHASE_GATE_ALLOWLIST => phase.merkle_root,
+            _ => self.merkle_root,
+        }
+    }
+}
+
+// mints received by one wallet, one per (collection, recipient); the
+// payer only funds the mint
+#[account]
+pub struct MintRecord {
+    pub collection_state: Pubkey,
+    pub wallet: Pubkey,
+    pub minted: u32,
+    pub bump: u8,
+    // per phase index of MintPhaseConfig::phases
+    pub phase_minted: [u32; MAX_MINT_PHASES],
+    // start_ts of the phase each phase_minted entry counts, a different
+    // phase at that index after set_mint_phases starts from zero
+    pub phase_start_ts: [i64; MAX_MINT_PHASES],
+}
+
+impl MintRecord {
+    pub const SPACE: usize = 8 + std::mem::size_of::<MintRecord>() + 32;
+
+    // mints so far in `phase`, or in total without phases
+    pub fn used(&self, phase: Option<&(usize, MintPhase)>) -> u32 {
+        match phase {
+            Some((index, phase)) if self.phase_start_ts[*index] == phase.start_ts => {
+                self.phase_minted[*index]
+            }
+            Some(_) => 0,
+            None => self.minted,
+        }
+    }
+
+    pub fn record_mints(
+        &mut self,
+        collection_state: Pubkey,
+        wallet: Pubkey,
+        bump: u8,
+        count: u32,
+        phase: Option<&(usize, MintPhase)>,
+        max_mints_per_wallet: Option<u32>,
+    ) -> Result<()> {
+        if self.wallet == Pubkey::default() {
+            self.collection_state = collection_state;
+            self.wallet = wallet;
+            self.bump = bump;
+        }
+        self.minted = self.minted
+            .checked_add(count)
+            .ok_or(InterchangeableNFTError::MathOverflow)?;
+        if let Some(max_mints_per_wallet) = max_mints_per_wallet {
+            require!(
+                self.minted <= max_mints_per_wallet,
+                InterchangeableNFTError::WalletMintLimitExceeded
+            );
+        }
+
+        if let Some((index, phase)) = phase {
+            if self.phase_start_ts[*index] != phase.start_ts {
+                self.phase_start_ts[*index] = phase.start_ts;
+                self.phase_minted[*index] = 0;
+            }
+            let phase_minted = &mut self.phase_minted[*index];
+            *phase_minted = phase_minted
+                .checked_add(count)
+                .ok_or(InterchangeableNFTError::MathOverflow)?;
+            require!(
+                phase.wallet_cap == 0 || *phase_minted <= phase.wallet_cap,
+                InterchangeableNFTError::WalletMintLimitExceeded
+            );
+        }
+        Ok(())
+    }
+}
+
+// marks a gate NFT as spent in a PHASE_GATE_TOKEN_ONCE phase,
+// one per (collection, gate NFT mint)
+#[account]
+pub struct GateRecord {
+    pub collection_state: Pubkey,
+    pub gate_mint: Pubkey,
+    pub used_by: Pubkey,
+    pub bump: u8,
+}
+
+impl GateRecord {
+    pub const SPACE: usize = 8 + std::mem::size_of::<GateRecord>() + 32;
+}
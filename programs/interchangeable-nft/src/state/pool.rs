@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::*, utils::random_u64};
+
+// metadata ids of a shuffled collection. Token ids are still minted in
+// order; token_id i gets metadata id ids[i] once the forward Fisher–Yates
+// shuffle has passed position i. NFTs minted before a delayed reveal are
+// drawn from the reveal seed alone, lazily up to the highest token_id asked
+// for. Every later NFT is drawn by its own mint, which also mixes in the
+// newest slot hash so the next id can't be read off the public seed.
+// ids[i] == 0 means position i still holds id i, otherwise it holds
+// ids[i] - 1. Created by the client and assigned with initialize_id_pool.
+#[account(zero_copy)]
+pub struct IdPool {
+    pub collection_state: Pubkey,
+    pub ids: [u16; MAX_MAX_SUPPLY as usize],
+    // set at initialize_id_pool, or by settle_reveal for delayed reveals
+    pub seed: [u8; 32],
+    // little-endian u64, positions below it are final
+    pub shuffled: [u8; 8],
+    // little-endian u64, NFTs minted before the reveal
+    pub pre_reveal_supply: [u8; 8],
+}
+
+impl IdPool {
+    pub const SPACE: usize = 8 + std::mem::size_of::<IdPool>();
+
+    fn id_at(&self, index: usize) -> u64 {
+        match self.ids[index] {
+            0 => index as u64,
+            stored => stored as u64 - 1,
+        }
+    }
+
+    pub fn shuffled(&self) -> u64 {
+        u64::from_le_bytes(self.shuffled)
+    }
+
+    pub fn pre_reveal_supply(&self) -> u64 {
+        u64::from_le_bytes(self.pre_reveal_supply)
+    }
+
+    // metadata id of `token_id`, shuffling every position up to it first.
+    // `entropy` is only used for positions minted after the reveal, which
+    // are drawn by their own mint in token_id order
+    pub fn metadata_id(&mut self, token_id: u64, max_supply: u64, entropy: &[u8]) -> Result<u64> {
+        require!(self.seed != [0; 32], InterchangeableNFTError::NotPreReveal);
+        require!(
+            token_id < max_supply && max_supply <= MAX_MAX_SUPPLY,
+            InterchangeableNFTError::NoAvailableNFTs
+        );
+
+        let pre_reveal_supply = self.pre_reveal_supply();
+        let mut position = self.shuffled();
+        while position <= token_id {
+            let entropy = if position < pre_reveal_supply { &[][..] } else { entropy };
+            let offset = random_u64(&[&self.seed, &position.to_le_bytes(), entropy])
+                % (max_supply - position);
+            let (index, other) = (position as usize, (position + offset) as usize);
+            let (id, other_id) = (self.id_at(index), self.id_at(other));
+            self.ids[index] = (other_id + 1) as u16;
+            self.ids[other] = (id + 1) as u16;
+            position += 1;
+        }
+        self.shuffled = position.to_le_bytes();
+
+        Ok(self.id_at(token_id as usize))
+    }
+}
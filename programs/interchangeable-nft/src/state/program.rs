@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::*};
+
+// protocol-wide configuration, one per program at PROGRAM_STATE_SEED
+#[account]
+pub struct ProgramState {
+    pub admin: Pubkey,
+    pub max_redeem_fee: u16,
+    pub bump: u8,
+    // wallet of the protocol fee token accounts
+    pub fee_receiver: Pubkey,
+    pub default_redeem_fee: u16,
+    pub paused: bool,
+    // empty list allows any payment mint
+    pub allowed_payment_mints: Vec<Pubkey>,
+    // holders can still redeem, new mints and pulls are blocked
+    pub redeem_only: bool,
+    // share of every redeem and swap fee paid to fee_receiver
+    pub protocol_fee_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProgramStateParams {
+    pub admin: Option<Pubkey>,
+    pub fee_receiver: Option<Pubkey>,
+    pub default_redeem_fee: Option<u16>,
+    pub max_redeem_fee: Option<u16>,
+    pub paused: Option<bool>,
+    pub allowed_payment_mints: Option<Vec<Pubkey>>,
+    pub protocol_fee_bps: Option<u16>,
+}
+
+impl ProgramState {
+    pub const SPACE: usize = 8 + std::mem::size_of::<ProgramState>() + 64
+        + MAX_ALLOWED_PAYMENT_MINTS * 32;
+
+    pub fn validate_redeem_fee(&self, redeem_fee: u16) -> Result<()> {
+        require!(
+            redeem_fee <= self.max_redeem_fee && redeem_fee <= MAX_REDEEM_FEE_BPS,
+            InterchangeableNFTError::RedeemFeeTooHigh
+        );
+        Ok(())
+    }
+
+    // a swap never costs more than the redeem fee cap
+    pub fn validate_swap_fee(&self, swap_fee: u16) -> Result<()> {
+        require!(
+            swap_fee <= self.max_redeem_fee && swap_fee <= MAX_REDEEM_FEE_BPS,
+            InterchangeableNFTError::SwapFeeTooHigh
+        );
+        Ok(())
+    }
+
+    pub fn check_not_paused(&self) -> Result<()> {
+        require!(!self.paused, InterchangeableNFTError::ProtocolPaused);
+        Ok(())
+    }
+
+    // mint and pull are blocked by the global pause and by redeem-only mode
+    pub fn check_entry_allowed(&self) -> Result<()> {
+        self.check_not_paused()?;
+        require!(!self.redeem_only, InterchangeableNFTError::ProtocolRedeemOnly);
+        Ok(())
+    }
+
+    pub fn validate_payment_mint(&self, payment_mint: &Pubkey) -> Result<()> {
+        require!(
+            self.allowed_payment_mints.is_empty()
+                || self.allowed_payment_mints.contains(payment_mint),
+            InterchangeableNFTError::PaymentMintNotAllowed
+        );
+        Ok(())
+    }
+
+    // collections above a lowered cap are charged the cap
+    pub fn effective_redeem_fee(&self, redeem_fee: u16) -> u16 {
+        redeem_fee.min(self.max_redeem_fee).min(MAX_REDEEM_FEE_BPS)
+    }
+
+    // swap fees share the redeem fee cap
+    pub fn effective_swap_fee(&self, swap_fee: u16) -> u16 {
+        swap_fee.min(self.max_redeem_fee).min(MAX_REDEEM_FEE_BPS)
+    }
+
+    // (collection, protocol) parts of a collection fee
+    pub fn split_fee(&self, fee_amount: u64) -> Result<(u64, u64)> {
+        let protocol_fee = fee_amount
+            .checked_mul(self.protocol_fee_bps as u64)
+            .ok_or(InterchangeableNFTError::MathOverflow)?
+            / 10000;
+        Ok((fee_amount - protocol_fee, protocol_fee))
+    }
+
+    pub fn apply(&mut self, params: ProgramStateParams) -> Result<()> {
+        if let Some(admin) = params.admin {
+            self.admin = admin;
+        }
+        if let Some(fee_receiver) = params.fee_receiver {
+            self.fee_receiver = fee_receiver;
+        }
+        if let Some(max_redeem_fee) = params.max_redeem_fee {
+            require!(
+                max_redeem_fee <= MAX_REDEEM_FEE_BPS,
+                InterchangeableNFTError::ExceedMaxValue
+            );
+            self.max_redeem_fee = max_redeem_fee;
+        }
+        if let Some(default_redeem_fee) = params.default_redeem_fee {
+            self.default_redeem_fee = default_redeem_fee;
+        }
+        if let Some(paused) = params.paused {
+            self.paused = paused;
+        }
+        if let Some(allowed_payment_mints) = params.allowed_payment_mints {
+            require!(
+                allowed_payment_mints.len() <= MAX_ALLOWED_PAYMENT_MINTS,
+                InterchangeableNFTError::ExceedMaxValue
+            );
+            self.allowed_payment_mints = allowed_payment_mints;
+        }
+        if let Some(protocol_fee_bps) = params.protocol_fee_bps {
+            require!(
+                protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS,
+                InterchangeableNFTError::ExceedMaxValue
+            );
+            self.protocol_fee_bps = protocol_fee_bps;
+        }
+
+        self.validate_redeem_fee(self.default_redeem_fee)
+    }
+}
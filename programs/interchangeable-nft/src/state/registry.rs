@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, error::*};
+
+// token_id -> mint for every NFT minted by the collection,
+// created by the client and assigned with initialize_token_registry
+#[account(zero_copy)]
+pub struct TokenRegistry {
+    pub collection_state: Pubkey,
+    pub mints: [Pubkey; MAX_MAX_SUPPLY as usize],
+}
+
+impl TokenRegistry {
+    pub const SPACE: usize = 8 + std::mem::size_of::<TokenRegistry>();
+
+    pub fn mint_of(&self, token_id: u64) -> Result<Pubkey> {
+        let mint = self.mints
+            .get(token_id as usize)
+            .copied()
+            .unwrap_or_default();
+        require!(mint != Pubkey::default(), InterchangeableNFTError::InvalidTokenId);
+        Ok(mint)
+    }
+
+    pub fn register(&mut self, token_id: u64, mint: Pubkey) -> Result<()> {
+        let entry = self.mints
+            .get_mut(token_id as usize)
+            .ok_or(InterchangeableNFTError::InvalidTokenId)?;
+        require!(
+            *entry == Pubkey::default(),
+            InterchangeableNFTError::TokenAlreadyRegistered
+        );
+        *entry = mint;
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use solana_program::hash::hashv;
+
+use crate::error::InterchangeableNFTError;
+
+// SlotHashes is too large to deserialize on-chain, entries are read in place:
+// u64 length followed by (u64 slot, [u8; 32] hash), newest first
+pub fn find_slot_hash(slot_hashes: &AccountInfo, slot: u64) -> Result<Option<[u8; 32]>> {
+    let data = slot_hashes.try_borrow_data()?;
+    let len = data
+        .get(..8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        .ok_or(InterchangeableNFTError::InvalidSlotHashes)?;
+
+    for i in 0..len {
+        let offset = 8 + i * 40;
+        let entry = data
+            .get(offset..offset + 40)
+            .ok_or(InterchangeableNFTError::InvalidSlotHashes)?;
+        let entry_slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if entry_slot == slot {
+            return Ok(Some(entry[8..].try_into().unwrap()));
+        }
+        if entry_slot < slot {
+            break;
+        }
+    }
+
+    Ok(None)
+}
+
+// hash of the newest entry, the slot before the current one
+pub fn latest_slot_hash(slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    let entry = data
+        .get(8..48)
+        .ok_or(InterchangeableNFTError::InvalidSlotHashes)?;
+    Ok(entry[8..].try_into().unwrap())
+}
+
+pub fn random_u64(parts: &[&[u8]]) -> u64 {
+    let hash = hashv(parts).to_bytes();
+    u64::from_le_bytes(hash[..8].try_into().unwrap())
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+use solana_program::hash::hashv;
+
+// allowlist leaf, quota 0 means unlimited and price 0 means mint_price
+pub fn allowlist_leaf(wallet: &Pubkey, quota: u32, price: u64) -> [u8; 32] {
+    hashv(&[wallet.as_ref(), &quota.to_le_bytes(), &price.to_le_bytes()]).to_bytes()
+}
+
+// sorted-pair sha256 merkle proof
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
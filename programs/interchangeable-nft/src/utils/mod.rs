@@ -1,3 +1,7 @@
 pub mod payment;
+pub mod entropy;
+pub mod merkle;
 
-pub use payment::*;
\ No newline at end of file
+pub use payment::*;
+pub use entropy::*;
+pub use merkle::*;
\ No newline at end of file
@@ -0,0 +1,190 @@
+use anchor_lang::{
+    prelude::*, solana_program::instruction::Instruction, AccountDeserialize, AccountSerialize,
+    InstructionData, ToAccountMetas,
+};
+use interchangeable_nft::{
+    constants::PAUSE_ALL,
+    error::InterchangeableNFTError,
+    state::{CollectionState, RoyaltyConfig},
+    ID,
+};
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+// anchor's entry wants one lifetime for the slice and the accounts
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    interchangeable_nft::entry(program_id, accounts, data)
+}
+
+fn collection_state(authority: Pubkey, collection_mint: Pubkey, bump: u8) -> CollectionState {
+    CollectionState {
+        authority,
+        collection_mint,
+        payment_token_mint: Pubkey::new_unique(),
+        mint_price: 1_000_000,
+        max_supply: 100,
+        next_token_id: 0,
+        base_uri: "https://example.com/".to_string(),
+        pause_flags: 0,
+        bump,
+        name: "Test".to_string(),
+        symbol: "TST".to_string(),
+        fee_receiver: authority,
+        royalty_config: RoyaltyConfig { basis_points: 0, receiver: authority },
+        redeem_fee: 0,
+        token_decimals: 6,
+        seed_authority: authority,
+        pending_authority: None,
+        vault_nft_count: 0,
+        token_registry: Pubkey::default(),
+        pending_random_pulls: 0,
+        swap_fee: 0,
+        max_mints_per_wallet: None,
+        reserved_supply: 0,
+        reserved_minted: 0,
+        placeholder_uri: String::new(),
+        provenance_hash: [0; 32],
+        revealed: false,
+        reveal_offset: 0,
+        id_pool: Pubkey::default(),
+        offset_seed_hash: [0; 32],
+        offset_seed: [0; 32],
+        reveal_slot: 0,
+    }
+}
+
+fn state_account(state: &CollectionState, owner: Pubkey) -> Account {
+    let mut data = Vec::with_capacity(CollectionState::SPACE);
+    state.try_serialize(&mut data).unwrap();
+    data.resize(CollectionState::SPACE, 0);
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn collection_pda(authority: &Pubkey, collection_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"collection", authority.as_ref(), collection_mint.as_ref()],
+        &ID,
+    )
+}
+
+fn pause_ix(authority: Pubkey, collection_state: Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: interchangeable_nft::accounts::AdminOnly {
+            authority,
+            collection_state,
+        }
+        .to_account_metas(None),
+        data: interchangeable_nft::instruction::Pause {}.data(),
+    }
+}
+
+// a collection owned by `authority`, stored at `address` and owned by `owner`
+async fn start(
+    authority: &Keypair,
+    address: Option<Pubkey>,
+    owner: Pubkey,
+) -> (ProgramTestContext, Pubkey) {
+    let mut program_test =
+        ProgramTest::new("interchangeable_nft", ID, processor!(process_instruction));
+    let collection_mint = Pubkey::new_unique();
+    let (pda, bump) = collection_pda(&authority.pubkey(), &collection_mint);
+    let address = address.unwrap_or(pda);
+    program_test.add_account(
+        address,
+        state_account(&collection_state(authority.pubkey(), collection_mint, bump), owner),
+    );
+    (program_test.start_with_context().await, address)
+}
+
+async fn fund(context: &mut ProgramTestContext, wallet: &Pubkey) {
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&context.payer.pubkey(), wallet, 1_000_000_000)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+async fn pause(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    collection_state: Pubkey,
+) -> std::result::Result<(), BanksClientError> {
+    fund(context, &signer.pubkey()).await;
+    let tx = Transaction::new_signed_with_payer(
+        &[pause_ix(signer.pubkey(), collection_state)],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+fn assert_custom_error(result: std::result::Result<(), BanksClientError>, code: u32) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(0, InstructionError::Custom(actual)) => {
+            assert_eq!(actual, code)
+        }
+        err => panic!("unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn pause_by_authority_succeeds() {
+    let authority = Keypair::new();
+    let (mut context, address) = start(&authority, None, ID).await;
+
+    pause(&mut context, &authority, address).await.unwrap();
+
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    let state = CollectionState::try_deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(state.pause_flags, PAUSE_ALL);
+}
+
+#[tokio::test]
+async fn pause_rejects_wrong_signer() {
+    let authority = Keypair::new();
+    let (mut context, address) = start(&authority, None, ID).await;
+
+    let result = pause(&mut context, &Keypair::new(), address).await;
+    assert_custom_error(result, InterchangeableNFTError::OnlyOwner.into());
+}
+
+#[tokio::test]
+async fn pause_rejects_spoofed_collection_state() {
+    // program-owned and naming the signer as authority, but not at the collection PDA
+    let attacker = Keypair::new();
+    let (mut context, address) = start(&attacker, Some(Pubkey::new_unique()), ID).await;
+
+    let result = pause(&mut context, &attacker, address).await;
+    assert_custom_error(result, ErrorCode::ConstraintSeeds.into());
+}
+
+#[tokio::test]
+async fn pause_rejects_foreign_collection_state() {
+    // at the collection PDA but owned by another program
+    let attacker = Keypair::new();
+    let (mut context, address) = start(&attacker, None, Pubkey::new_unique()).await;
+
+    let result = pause(&mut context, &attacker, address).await;
+    assert_custom_error(result, ErrorCode::AccountOwnedByWrongProgram.into());
+}
@@ -0,0 +1,70 @@
+use bytemuck::Zeroable;
+use interchangeable_nft::{constants::MAX_MAX_SUPPLY, state::IdPool};
+
+fn revealed_pool(seed: u8) -> Box<IdPool> {
+    let mut pool = Box::new(IdPool::zeroed());
+    pool.seed = [seed; 32];
+    pool
+}
+
+fn draw_all(pool: &mut IdPool, max_supply: u64, entropy: &[u8]) -> Vec<u64> {
+    (0..max_supply)
+        .map(|token_id| pool.metadata_id(token_id, max_supply, entropy).unwrap())
+        .collect()
+}
+
+#[test]
+fn metadata_ids_never_repeat() {
+    for max_supply in [1, 2, 7, 100, MAX_MAX_SUPPLY] {
+        let mut pool = revealed_pool(1);
+        let mut ids = draw_all(&mut pool, max_supply, &[7; 32]);
+        ids.sort_unstable();
+        assert_eq!(ids, (0..max_supply).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn pool_runs_out_at_max_supply() {
+    let max_supply = 100;
+    let mut pool = revealed_pool(2);
+    draw_all(&mut pool, max_supply, &[7; 32]);
+
+    assert_eq!(pool.shuffled(), max_supply);
+    assert!(pool.metadata_id(max_supply, max_supply, &[]).is_err());
+}
+
+#[test]
+fn metadata_ids_are_stable() {
+    let max_supply = 100;
+    let mut in_order = revealed_pool(3);
+    let expected = draw_all(&mut in_order, max_supply, &[]);
+
+    // drawing a later id first shuffles the positions before it the same way
+    let mut skipped = revealed_pool(3);
+    assert_eq!(skipped.metadata_id(50, max_supply, &[]).unwrap(), expected[50]);
+    assert_eq!(draw_all(&mut skipped, max_supply, &[]), expected);
+}
+
+#[test]
+fn pool_is_sealed_before_reveal() {
+    let mut pool = Box::new(IdPool::zeroed());
+    assert!(pool.metadata_id(0, 100, &[]).is_err());
+}
+
+#[test]
+fn only_post_reveal_ids_use_mint_entropy() {
+    let max_supply = 100;
+    let mut cranked = revealed_pool(4);
+    cranked.pre_reveal_supply = 10u64.to_le_bytes();
+    let expected = draw_all(&mut cranked, max_supply, &[]);
+
+    // a minter picking the slot can't move the NFTs minted before the reveal
+    let mut minted = revealed_pool(4);
+    minted.pre_reveal_supply = 10u64.to_le_bytes();
+    let mut ids = draw_all(&mut minted, max_supply, &[8; 32]);
+    assert_eq!(ids[..10], expected[..10]);
+    assert_ne!(ids[10..], expected[10..]);
+
+    ids.sort_unstable();
+    assert_eq!(ids, (0..max_supply).collect::<Vec<_>>());
+}
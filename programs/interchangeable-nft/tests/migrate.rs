@@ -0,0 +1,150 @@
+use anchor_lang::{
+    prelude::*, solana_program::instruction::Instruction, AccountDeserialize, AnchorSerialize,
+    Discriminator, InstructionData, ToAccountMetas,
+};
+use interchangeable_nft::{
+    constants::PAUSE_ALL,
+    error::InterchangeableNFTError,
+    state::{CollectionState, LegacyCollectionState, RoyaltyConfig},
+    ID,
+};
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+// 8 + size_of::<CollectionState>() + 200 as allocated by the first release
+const LEGACY_SPACE: usize = 472;
+
+// anchor's entry wants one lifetime for the slice and the accounts
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    interchangeable_nft::entry(program_id, accounts, data)
+}
+
+fn legacy_state(authority: Pubkey, collection_mint: Pubkey, bump: u8) -> LegacyCollectionState {
+    LegacyCollectionState {
+        authority,
+        collection_mint,
+        payment_token_mint: Pubkey::new_unique(),
+        mint_price: 1_000_000,
+        max_supply: 100,
+        next_token_id: 42,
+        base_uri: "https://arweave.net/interchangeable-collection-metadata".to_string(),
+        paused: true,
+        bump,
+        name: "Legacy Collection".to_string(),
+        symbol: "LGCY".to_string(),
+        fee_receiver: authority,
+        royalty_config: RoyaltyConfig { basis_points: 500, receiver: authority },
+        redeem_fee: 250,
+        token_decimals: 6,
+    }
+}
+
+fn legacy_account(state: &LegacyCollectionState) -> Account {
+    let mut data = CollectionState::DISCRIMINATOR.to_vec();
+    state.serialize(&mut data).unwrap();
+    data.resize(LEGACY_SPACE, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(LEGACY_SPACE),
+        data,
+        owner: ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn start(authority: &Keypair) -> (ProgramTestContext, Pubkey, LegacyCollectionState) {
+    let mut program_test =
+        ProgramTest::new("interchangeable_nft", ID, processor!(process_instruction));
+    let collection_mint = Pubkey::new_unique();
+    let (address, bump) = Pubkey::find_program_address(
+        &[b"collection", authority.pubkey().as_ref(), collection_mint.as_ref()],
+        &ID,
+    );
+    let state = legacy_state(authority.pubkey(), collection_mint, bump);
+    program_test.add_account(address, legacy_account(&state));
+    (program_test.start_with_context().await, address, state)
+}
+
+async fn migrate(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    collection_state: Pubkey,
+) -> std::result::Result<(), BanksClientError> {
+    let fund = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&context.payer.pubkey(), &signer.pubkey(), 1_000_000_000)],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(fund).await.unwrap();
+
+    let ix = Instruction {
+        program_id: ID,
+        accounts: interchangeable_nft::accounts::MigrateCollectionState {
+            authority: signer.pubkey(),
+            collection_state,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: interchangeable_nft::instruction::MigrateCollectionState {}.data(),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn migrates_baseline_layout() {
+    let authority = Keypair::new();
+    let (mut context, address, legacy) = start(&authority).await;
+
+    migrate(&mut context, &authority, address).await.unwrap();
+
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), CollectionState::SPACE);
+    let state = CollectionState::try_deserialize(&mut &account.data[..]).unwrap();
+    assert_eq!(state.authority, legacy.authority);
+    assert_eq!(state.seed_authority, legacy.authority);
+    assert_eq!(state.collection_mint, legacy.collection_mint);
+    assert_eq!(state.payment_token_mint, legacy.payment_token_mint);
+    assert_eq!(state.mint_price, legacy.mint_price);
+    assert_eq!(state.next_token_id, legacy.next_token_id);
+    assert_eq!(state.base_uri, legacy.base_uri);
+    assert_eq!(state.name, legacy.name);
+    assert_eq!(state.symbol, legacy.symbol);
+    assert_eq!(state.redeem_fee, legacy.redeem_fee);
+    assert_eq!(state.royalty_config.basis_points, legacy.royalty_config.basis_points);
+    assert_eq!(state.bump, legacy.bump);
+    assert_eq!(state.pause_flags, PAUSE_ALL);
+    assert_eq!(state.pending_authority, None);
+    assert!(!state.revealed);
+}
+
+#[tokio::test]
+async fn migrate_rejects_wrong_signer() {
+    let authority = Keypair::new();
+    let (mut context, address, _) = start(&authority).await;
+
+    let result = migrate(&mut context, &Keypair::new(), address).await;
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(0, InstructionError::Custom(code)) => {
+            assert_eq!(code, u32::from(InterchangeableNFTError::OnlyOwner))
+        }
+        err => panic!("unexpected error: {err:?}"),
+    }
+}